@@ -0,0 +1,171 @@
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use std::{hint::black_box, io};
+
+use badges::{Badge, Colour, Render, Segment};
+use criterion::{
+    criterion_group, criterion_main,
+    measurement::{Measurement, ValueFormatter},
+    BenchmarkId, Criterion, Throughput,
+};
+
+/// Measures the number of heap allocations made by a benchmark, rather than its run time.
+struct Allocations;
+
+impl Measurement for Allocations {
+    type Intermediate = usize;
+    type Value = usize;
+
+    fn start(&self) -> usize {
+        common::allocated()
+    }
+
+    fn end(&self, start: usize) -> usize {
+        common::allocated() - start
+    }
+
+    fn add(&self, a: &usize, b: &usize) -> usize {
+        a + b
+    }
+
+    fn zero(&self) -> usize {
+        0
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn to_f64(&self, value: &usize) -> f64 {
+        *value as f64
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        self
+    }
+}
+
+impl ValueFormatter for Allocations {
+    fn scale_values(&self, _typical_value: f64, _values: &mut [f64]) -> &'static str {
+        "allocations"
+    }
+
+    fn scale_throughputs(
+        &self,
+        _typical_value: f64,
+        _throughput: &Throughput,
+        _values: &mut [f64],
+    ) -> &'static str {
+        "allocations"
+    }
+
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        "allocations"
+    }
+}
+
+const LOGO: &str = "data:image/svg+xml;base64,PHN2ZyB4bWxucz0iaHR0cDovL3d3dy53My5vcmcvMjAwMC9zdmciIHZpZXdCb3g9IjAgMCAxNCAxNCI+PGNpcmNsZSBjeD0iNyIgY3k9IjciIHI9IjciIGZpbGw9IiNmZmYiLz48L3N2Zz4=";
+
+fn badges() -> Vec<(&'static str, Badge<'static>)> {
+    vec![
+        (
+            "typical",
+            Badge::builder().label("build").message("passing").build(),
+        ),
+        (
+            "long",
+            Badge::builder()
+                .label("deploy/feature/some-very-long-branch-name-for-testing")
+                .message("fix: handle the case where the badge message is extremely long")
+                .colour(Colour::ORANGE)
+                .build(),
+        ),
+        (
+            "unicode",
+            Badge::builder()
+                .label("ビルド状態")
+                .message("成功 ✓ — Ünïcødé ✨🚀")
+                .build(),
+        ),
+        (
+            "logo",
+            Badge::builder()
+                .label("rust")
+                .message("1.95")
+                .logo(LOGO)
+                .build(),
+        ),
+        (
+            "segments",
+            Badge::builder()
+                .label("coverage")
+                .message("91%")
+                .segment(Segment::new("+2.1%").colour(Colour::SEA_GREEN))
+                .message_link("https://example.com/coverage")
+                .build(),
+        ),
+    ]
+}
+
+fn render(c: &mut Criterion) {
+    let badges = badges();
+    let mut buf = String::with_capacity(8192);
+    let mut group = c.benchmark_group("render");
+    for (name, badge) in &badges {
+        buf.clear();
+        badge.render_to(&mut buf).expect("failed to render");
+        group.throughput(Throughput::Bytes(buf.len() as u64));
+        group.bench_with_input(BenchmarkId::new("render", name), badge, |b, badge| {
+            b.iter(|| black_box(badge.render()));
+        });
+        group.bench_with_input(BenchmarkId::new("render_to", name), badge, |b, badge| {
+            b.iter(|| {
+                buf.clear();
+                badge.render_to(&mut buf).expect("failed to render");
+                black_box(&buf);
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("render_to_io", name), badge, |b, badge| {
+            b.iter(|| {
+                badge
+                    .render_to_io(&mut io::sink())
+                    .expect("failed to render");
+            });
+        });
+    }
+    group.finish();
+}
+
+fn build(c: &mut Criterion) {
+    c.bench_function("build", |b| {
+        b.iter(|| {
+            Badge::builder()
+                .label(black_box("build"))
+                .message(black_box("passing"))
+                .build()
+        });
+    });
+}
+
+fn allocations(c: &mut Criterion<Allocations>) {
+    let mut group = c.benchmark_group("allocations");
+    let mut buf = String::with_capacity(8192);
+    for (name, badge) in &badges() {
+        group.bench_with_input(BenchmarkId::new("render", name), badge, |b, badge| {
+            b.iter(|| black_box(badge.render()));
+        });
+        group.bench_with_input(BenchmarkId::new("render_to", name), badge, |b, badge| {
+            b.iter(|| {
+                buf.clear();
+                badge.render_to(&mut buf).expect("failed to render");
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, render, build);
+criterion_group! {
+    name = allocation_benches;
+    config = Criterion::default().with_measurement(Allocations).without_plots();
+    targets = allocations
+}
+criterion_main!(benches, allocation_benches);
@@ -0,0 +1,76 @@
+use std::{fmt, time::Duration};
+
+use crate::{
+    colour::Colour,
+    xml::{Tag, Writer},
+};
+
+/// How a [`Segment`](crate::Segment) of a badge is animated, such as to draw attention to a
+/// failing status on a wall of badges.
+///
+/// Animations are rendered as SMIL `<animate>` elements, which browsers play in `<img>` elements
+/// too. Renderers without animation support, such as PNG rasterisers, draw the badge as it is
+/// before the animation starts.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use badges::{Animation, Badge, Colour, Render};
+/// let badge = Badge::builder()
+///     .label("build")
+///     .message("failing")
+///     .colour(Colour::RED)
+///     .animation(Animation::Blink { period: Duration::from_secs(1) })
+///     .build();
+/// assert!(badge.render().contains("<animate"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Animation {
+    /// Fade the background of the segment to `colour` and back, once every `period`.
+    Pulse {
+        /// The colour the background fades to.
+        colour: Colour,
+        /// How long a fade there and back takes.
+        period: Duration,
+    },
+    /// Hide the text of the segment for half of every `period`.
+    Blink {
+        /// How long the text takes to be hidden and shown again.
+        period: Duration,
+    },
+}
+
+/// Start an `<animate>` element that changes `attribute` through `values` every `period`,
+/// forever.
+pub fn animate<'t, 'w>(
+    w: &'t mut Writer<'w>,
+    attribute: &str,
+    values: &dyn fmt::Display,
+    period: Duration,
+) -> Tag<'t, 'w> {
+    w.element("animate")
+        .attr("attributeName", attribute)
+        .attr_display("values", values)
+        .attr_display("dur", &format_args!("{}s", period.as_secs_f32()))
+        .attr("repeatCount", "indefinite")
+}
+
+/// Write an `<animate>` element that shows the frame at `index` of `count` frames, each shown
+/// for `period` in turn, by switching its opacity.
+pub fn cycle(w: &mut Writer, index: usize, count: usize, period: Duration) -> fmt::Result {
+    #[allow(clippy::cast_precision_loss)]
+    let key_time = |index: usize| index as f32 / count as f32;
+    // saturate rather than overflow for absurdly long periods, which are effectively forever
+    let total = period.saturating_mul(u32::try_from(count).unwrap_or(u32::MAX));
+    let animate = if index == 0 {
+        animate(w, "opacity", &"1;0", total)
+            .attr_display("keyTimes", &format_args!("0;{}", key_time(1)))
+    } else {
+        animate(w, "opacity", &"0;1;0", total).attr_display(
+            "keyTimes",
+            &format_args!("0;{};{}", key_time(index), key_time(index + 1)),
+        )
+    };
+    animate.attr("calcMode", "discrete").empty()
+}
@@ -1,12 +1,23 @@
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    fmt::{self, Write},
+    hash::{Hash, Hasher},
+    time::Duration,
+};
 
 use crate::{
-    colour::Colour,
-    font::measure,
-    traits::Render,
-    xml::{Content, Element, ElementList},
-    BRIGHTNESS_THRESHOLD, FONT_FAMILY, FONT_SCALE_DOWN_VALUE, FONT_SCALE_UP_FACTOR, HEIGHT,
-    LOGO_HEIGHT, SHADOW, VERTICAL_MARGIN,
+    animation::{self, Animation},
+    colour::{Colour, BLUE, LIGHT_GREY},
+    format,
+    geometry::Geometry,
+    hash::Fnv1a,
+    layout::{Layout, Rect, SegmentLayout, TextBox},
+    scale::ColourScale,
+    segment::{LinkTarget, LogoSide, Segment},
+    sparkline::Sparkline,
+    traits::{self, IteratorExt, Render},
+    xml::{Tag, Writer},
+    BRIGHTNESS_THRESHOLD, FONT_FAMILY, FONT_SCALE_DOWN_VALUE, FONT_SCALE_UP_FACTOR,
 };
 
 const fn colours_for_background(colour: Colour) -> (Colour, Colour) {
@@ -23,23 +34,91 @@ const fn colours_for_background(colour: Colour) -> (Colour, Colour) {
     }
 }
 
-fn round_up_to_odd(val: f32) -> f32 {
-    if val.rem_euclid(2.0) == 0.0 {
-        val + 1.0
+/// The text and shadow colours for text over two backgrounds, such as the filled and unfilled
+/// parts of a progress bar: whichever are the most readable over the background they contrast
+/// with least.
+fn colours_for_backgrounds(a: Colour, b: Colour) -> (Colour, Colour) {
+    let contrast = |text: Colour| {
+        let brightness = text.brightness();
+        brightness
+            .abs_diff(a.brightness())
+            .min(brightness.abs_diff(b.brightness()))
+    };
+    let light = colours_for_background(Colour::BLACK);
+    let dark = colours_for_background(Colour::WHITE);
+    if contrast(light.0) >= contrast(dark.0) {
+        light
+    } else {
+        dark
+    }
+}
+
+/// The text and shadow colours for `segment` over its background, or over its dark background
+/// if `dark`.
+fn text_colours(segment: &Segment, dark: bool) -> (Colour, Colour) {
+    let mut colour = segment.colour;
+    let mut track_colour = segment.track_colour.unwrap_or(LIGHT_GREY);
+    if dark {
+        colour = segment.dark_colour.unwrap_or(colour);
+        track_colour = segment.dark_track_colour.unwrap_or(track_colour);
+    }
+    if segment.progress.is_some() {
+        colours_for_backgrounds(colour, track_colour)
     } else {
-        val
+        colours_for_background(colour)
     }
 }
 
-fn preferred_width(text: &str) -> f32 {
-    if text.is_empty() {
-        return 0.0;
+/// Whether `prefix` can be passed to [`Builder::id_prefix`]: an ASCII letter or `_`, followed by
+/// ASCII letters, digits, `-` and `_`.
+///
+/// Prefixes are written into XML ids and CSS class selectors as-is, so they are limited to
+/// characters that need no escaping in either.
+#[must_use]
+pub const fn is_valid_id_prefix(prefix: &str) -> bool {
+    let bytes = prefix.as_bytes();
+    if bytes.is_empty() || !(bytes[0].is_ascii_alphabetic() || bytes[0] == b'_') {
+        return false;
     }
-    round_up_to_odd(measure(text))
+    let mut i = 1;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if !(b.is_ascii_alphanumeric() || b == b'-' || b == b'_') {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Panic with a message naming `prefix` if it is not [a valid id prefix](is_valid_id_prefix).
+pub fn assert_valid_id_prefix(prefix: &str) {
+    assert!(
+        is_valid_id_prefix(prefix),
+        "invalid id prefix `{prefix}`: expected an ASCII letter or `_`, followed by ASCII \
+         letters, digits, `-` and `_`"
+    );
+}
+
+/// Where the logo is placed on a [`Badge`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogoPosition {
+    /// At the far left of the badge, before the label. This is the default.
+    #[default]
+    Left,
+    /// At the start of the message panel, between the label and the message.
+    Message,
+    /// At the far right of the badge, after the last segment.
+    ///
+    /// This is useful for trailing status glyphs like ✓ or ✗.
+    Right,
 }
 
 /// A Badge struct represents a badge that can be rendered to SVG.
 ///
+/// A badge is made up of one or more [`Segment`]s. Usually these are a label and a message, but
+/// any number of segments can be added with [`Builder::segment`].
+///
 /// # Example
 ///
 /// ```rust
@@ -47,14 +126,70 @@ fn preferred_width(text: &str) -> f32 {
 /// let badge = Badge::builder().message("Hello").build();
 /// println!("{}", badge.render());
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub struct Badge<'a> {
-    label_colour: Colour,
-    colour: Colour,
-    label: Option<&'a str>,
-    message: &'a str,
-    logo: Option<&'a str>,
-    logo_width: Option<f32>,
+    segments: Vec<Segment<'a>>,
+    id_prefix: Option<&'a str>,
+    geometry: Geometry,
+    style: Style<'a>,
+}
+
+/// How the text and background of a badge are drawn, apart from their colours.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Style<'a> {
+    shadow: bool,
+    gradient: bool,
+    /// The colour of the shadow, which depends on the background unless set.
+    shadow_colour: Option<Colour>,
+    shadow_opacity: Option<f32>,
+    font_family: Option<&'a str>,
+}
+
+impl Default for Style<'_> {
+    fn default() -> Self {
+        Self {
+            shadow: true,
+            gradient: true,
+            shadow_colour: None,
+            shadow_opacity: None,
+            font_family: None,
+        }
+    }
+}
+
+impl Hash for Style<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.shadow.hash(state);
+        self.gradient.hash(state);
+        self.shadow_colour.hash(state);
+        self.shadow_opacity.map(f32::to_bits).hash(state);
+        self.font_family.hash(state);
+    }
+}
+
+/// The ids of the elements of a rendered badge that other elements refer to.
+pub struct Ids {
+    prefix: String,
+    gradient: String,
+    clip: String,
+}
+
+impl Ids {
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.to_owned(),
+            gradient: format!("{prefix}-s"),
+            clip: format!("{prefix}-r"),
+        }
+    }
+
+    /// The class of a part of the segment at `index` that is restyled in dark mode: its
+    /// background for an empty `part`, `k` for the track of a progress bar, or `t` for its text
+    /// and `h` for its shadow.
+    fn dark_class<'i>(&'i self, index: usize, part: &'i str) -> impl fmt::Display + 'i {
+        let prefix = &self.prefix;
+        fmt::from_fn(move |f| write!(f, "{prefix}-{index}{part}"))
+    }
 }
 
 /// A Builder struct represents a builder for a [`Badge`].
@@ -62,24 +197,44 @@ pub struct Badge<'a> {
 pub struct Builder<'a> {
     label_colour: Option<Colour>,
     colour: Option<Colour>,
-    label: Option<&'a str>,
-    message: &'a str,
+    dark_label_colour: Option<Colour>,
+    dark_colour: Option<Colour>,
+    label: Option<Cow<'a, str>>,
+    message: Cow<'a, str>,
     logo: Option<&'a str>,
     logo_width: Option<f32>,
+    logo_position: LogoPosition,
+    label_link: Option<&'a str>,
+    message_link: Option<&'a str>,
+    link_target: LinkTarget,
+    id_prefix: Option<&'a str>,
+    max_width: Option<f32>,
+    max_chars: Option<usize>,
+    multi_line: bool,
+    geometry: Geometry,
+    style: Style<'a>,
+    animation: Option<Animation>,
+    cycle: Vec<Cow<'a, str>>,
+    cycle_period: Duration,
+    progress: Option<f32>,
+    track_colour: Option<Colour>,
+    dark_track_colour: Option<Colour>,
+    sparkline: Option<Sparkline>,
+    segments: Vec<Segment<'a>>,
 }
 
 impl<'a> Builder<'a> {
     /// Set the message for the badge.
     #[must_use]
-    pub const fn message(mut self, message: &'a str) -> Self {
-        self.message = message;
+    pub fn message(mut self, message: impl Into<Cow<'a, str>>) -> Self {
+        self.message = message.into();
         self
     }
 
     /// Set the label for the badge.
     #[must_use]
-    pub const fn label(mut self, label: &'a str) -> Self {
-        self.label = Some(label);
+    pub fn label(mut self, label: impl Into<Cow<'a, str>>) -> Self {
+        self.label = Some(label.into());
         self
     }
 
@@ -97,6 +252,39 @@ impl<'a> Builder<'a> {
         self
     }
 
+    /// Set the colour of the message when the viewer prefers a dark colour scheme.
+    ///
+    /// Badges with dark colours include a `<style>` element switching to them with the
+    /// `prefers-color-scheme` media query, so that they fit in with dark pages such as GitHub in
+    /// dark mode. Text colours follow the background colours.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use badges::{Badge, Colour, Render};
+    /// let badge = Badge::builder()
+    ///     .label("build")
+    ///     .message("passing")
+    ///     .dark_label_colour(Colour::from_colour_code(0x30_36_3d))
+    ///     .dark_colour(Colour::from_colour_code(0x23_86_36))
+    ///     .build();
+    /// assert!(badge.render().contains("@media (prefers-color-scheme:dark)"));
+    /// ```
+    #[must_use]
+    pub const fn dark_colour(mut self, dark_colour: Colour) -> Self {
+        self.dark_colour = Some(dark_colour);
+        self
+    }
+
+    /// Set the colour of the label when the viewer prefers a dark colour scheme.
+    ///
+    /// See [`Builder::dark_colour`].
+    #[must_use]
+    pub const fn dark_label_colour(mut self, dark_label_colour: Colour) -> Self {
+        self.dark_label_colour = Some(dark_label_colour);
+        self
+    }
+
     /// Set the logo for the badge.
     #[must_use]
     pub const fn logo(mut self, logo: &'a str) -> Self {
@@ -111,23 +299,329 @@ impl<'a> Builder<'a> {
         self
     }
 
+    /// Set where the logo is placed on the badge.
+    ///
+    /// Defaults to [`LogoPosition::Left`].
+    #[must_use]
+    pub const fn logo_position(mut self, logo_position: LogoPosition) -> Self {
+        self.logo_position = logo_position;
+        self
+    }
+
+    /// Set the URL the label links to.
+    ///
+    /// This has no effect if the badge has no label.
+    #[must_use]
+    pub const fn label_link(mut self, label_link: &'a str) -> Self {
+        self.label_link = Some(label_link);
+        self
+    }
+
+    /// Set the URL the message links to.
+    #[must_use]
+    pub const fn message_link(mut self, message_link: &'a str) -> Self {
+        self.message_link = Some(message_link);
+        self
+    }
+
+    /// Set where the label and message links open.
+    ///
+    /// Defaults to [`LinkTarget::Blank`]. Segments added with [`Builder::segment`] keep their
+    /// own [`Segment::link_target`].
+    #[must_use]
+    pub const fn link_target(mut self, link_target: LinkTarget) -> Self {
+        self.link_target = link_target;
+        self
+    }
+
+    /// Set the prefix used for the ids of elements in the rendered SVG.
+    ///
+    /// The rendered SVG refers to its gradient and clip path by id, so badges inlined into the
+    /// same HTML document must not share ids. By default, the prefix is derived from a hash of
+    /// the badge's contents, which is enough unless identical badges need different ids.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the prefix is not valid in both XML ids and CSS class selectors, which can be
+    /// checked beforehand with [`is_valid_id_prefix`](crate::is_valid_id_prefix).
+    #[must_use]
+    pub fn id_prefix(mut self, id_prefix: &'a str) -> Self {
+        assert_valid_id_prefix(id_prefix);
+        self.id_prefix = Some(id_prefix);
+        self
+    }
+
+    /// Set the maximum width of each segment of the badge, including its padding and logo.
+    ///
+    /// Text that does not fit is cut short with an ellipsis, so that long messages such as branch
+    /// names do not produce enormous badges. The title and `aria-label` of the badge keep the
+    /// full text. Segments added with [`Builder::segment`] that set their own
+    /// [`Segment::max_width`] keep it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use badges::{Badge, Render};
+    /// let badge = Badge::builder()
+    ///     .label("deploy")
+    ///     .message("feature/a-very-long-branch-name")
+    ///     .max_width(80.0)
+    ///     .build();
+    /// assert!(badge.layout().segments()[1].rect().width() <= 80.0);
+    /// assert!(badge.render().contains("<title>deploy: feature/a-very-long-branch-name</title>"));
+    /// ```
+    #[must_use]
+    pub const fn max_width(mut self, max_width: f32) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    /// Set the maximum number of characters in each segment of the badge, including the
+    /// ellipsis added when text is cut short.
+    ///
+    /// See [`Segment::max_chars`] for how characters are counted. Segments added with
+    /// [`Builder::segment`] that set their own maximum keep it.
+    #[must_use]
+    pub const fn max_chars(mut self, max_chars: usize) -> Self {
+        self.max_chars = Some(max_chars);
+        self
+    }
+
+    /// Lay out the text of every segment on several lines, making the badge taller.
+    ///
+    /// Text is split into lines at newlines, and wrapped to fit the [maximum
+    /// width](Builder::max_width) if one is set rather than cut short. See
+    /// [`Segment::multi_line`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use badges::{Badge, Render};
+    /// let badge = Badge::builder()
+    ///     .label("release")
+    ///     .message("v1.4.0\n2026-10-01")
+    ///     .multi_line()
+    ///     .build();
+    /// assert_eq!(badge.layout().segments()[1].line_count(), 2);
+    /// assert!(badge.layout().height() > 20.0);
+    /// assert!(badge.render().contains("<title>release: v1.4.0 2026-10-01</title>"));
+    /// ```
+    #[must_use]
+    pub const fn multi_line(mut self) -> Self {
+        self.multi_line = true;
+        self
+    }
+
+    /// Set the [`Geometry`] of the badge, such as its height, padding and font size.
+    ///
+    /// Defaults to [`Geometry::new`], which matches shields.io.
+    #[must_use]
+    pub const fn geometry(mut self, geometry: Geometry) -> Self {
+        self.geometry = geometry;
+        self
+    }
+
+    /// Set whether the text of the badge has a shadow. Defaults to `true`.
+    #[must_use]
+    pub const fn shadow(mut self, shadow: bool) -> Self {
+        self.style.shadow = shadow;
+        self
+    }
+
+    /// Set whether the badge has a gloss gradient over it. Defaults to `true`.
+    ///
+    /// Turning off both the gradient and the [shadow](Builder::shadow) gives a flat badge.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use badges::{Badge, Render};
+    /// let badge = Badge::builder()
+    ///     .label("build")
+    ///     .message("passing")
+    ///     .shadow(false)
+    ///     .gradient(false)
+    ///     .build();
+    /// let svg = badge.render();
+    /// assert!(!svg.contains("<linearGradient"));
+    /// assert!(!svg.contains("fill-opacity"));
+    /// ```
+    #[must_use]
+    pub const fn gradient(mut self, gradient: bool) -> Self {
+        self.style.gradient = gradient;
+        self
+    }
+
+    /// Set the colour of the shadow behind the text.
+    ///
+    /// By default, the shadow is dark behind light text and light behind dark text.
+    #[must_use]
+    pub const fn shadow_colour(mut self, shadow_colour: Colour) -> Self {
+        self.style.shadow_colour = Some(shadow_colour);
+        self
+    }
+
+    /// Set the opacity of the shadow behind the text, from `0.0` to `1.0`. Defaults to `0.3`.
+    #[must_use]
+    pub const fn shadow_opacity(mut self, shadow_opacity: f32) -> Self {
+        self.style.shadow_opacity = Some(shadow_opacity);
+        self
+    }
+
+    /// Set the font family of the text, as a CSS `font-family` list.
+    ///
+    /// Defaults to Verdana and similar fonts, like shields.io. Text is still measured as if it
+    /// were in Verdana, and stretched or squashed to that width.
+    #[must_use]
+    pub const fn font_family(mut self, font_family: &'a str) -> Self {
+        self.style.font_family = Some(font_family);
+        self
+    }
+
+    /// Animate the message of the badge. Badges are static unless this is called.
+    ///
+    /// See [`Animation`] for how animations are rendered.
+    #[must_use]
+    pub const fn animation(mut self, animation: Animation) -> Self {
+        self.animation = Some(animation);
+        self
+    }
+
+    /// Cycle the message through each of `messages` after the one set with
+    /// [`Builder::message`], showing each for `period`.
+    ///
+    /// The message panel is as wide as the widest message. See [`Segment::cycle`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use badges::{Badge, Render};
+    /// let badge = Badge::builder()
+    ///     .label("rust")
+    ///     .message("1.80")
+    ///     .cycle(["1.81", "nightly"], Duration::from_secs(2))
+    ///     .build();
+    /// let widest = Badge::builder().label("rust").message("nightly").build();
+    /// assert!((badge.layout().width() - widest.layout().width()).abs() < 1e-3);
+    /// assert!(badge.render().contains("<title>rust: 1.80 / 1.81 / nightly</title>"));
+    /// ```
+    #[must_use]
+    pub fn cycle<T: Into<Cow<'a, str>>>(
+        mut self,
+        messages: impl IntoIterator<Item = T>,
+        period: Duration,
+    ) -> Self {
+        self.cycle = messages.into_iter().map(Into::into).collect();
+        self.cycle_period = period;
+        self
+    }
+
+    /// Draw the message as a progress bar, filled with its colour for `fraction` of its width.
+    ///
+    /// See [`Segment::progress`] for details, and [`Badge::progress`] for a badge with the
+    /// percentage as its message.
+    #[must_use]
+    pub const fn progress(mut self, fraction: f32) -> Self {
+        self.progress = Some(fraction);
+        self
+    }
+
+    /// Set the colour of the unfilled part of the message when it is a
+    /// [progress bar](Builder::progress).
+    #[must_use]
+    pub const fn track_colour(mut self, track_colour: Colour) -> Self {
+        self.track_colour = Some(track_colour);
+        self
+    }
+
+    /// Set the colour of the unfilled part of the message when it is a
+    /// [progress bar](Builder::progress) and the viewer prefers a dark colour scheme.
+    ///
+    /// See [`Builder::dark_colour`].
+    #[must_use]
+    pub const fn dark_track_colour(mut self, dark_track_colour: Colour) -> Self {
+        self.dark_track_colour = Some(dark_track_colour);
+        self
+    }
+
+    /// Draw a [`Sparkline`] chart after the message, such as of the values the message is the
+    /// latest of.
+    ///
+    /// See [`Badge::sparkline`] for a badge with the latest value as its message.
+    #[must_use]
+    pub fn sparkline(mut self, sparkline: Sparkline) -> Self {
+        self.sparkline = Some(sparkline);
+        self
+    }
+
+    /// Append a [`Segment`] to the badge, after the message and any previously added segments.
+    #[must_use]
+    pub fn segment(mut self, segment: Segment<'a>) -> Self {
+        self.segments.push(segment);
+        self
+    }
+
     /// Build the [`Badge`].
     #[must_use]
     pub fn build(self) -> Badge<'a> {
-        Badge::new(
-            self.label,
-            self.message,
-            self.logo,
-            self.logo_width,
-            self.colour,
-            self.label_colour,
-        )
+        let mut segments = Vec::with_capacity(self.segments.len() + 2);
+        if let Some(label) = self.label.filter(|l| !l.is_empty()) {
+            let mut segment = Segment::new(label)
+                .colour(
+                    self.label_colour
+                        .unwrap_or(Colour::from_colour_code(0x55_55_55)),
+                )
+                .link_target(self.link_target);
+            segment.link = self.label_link;
+            segment.dark_colour = self.dark_label_colour;
+            segments.push(segment);
+        }
+        let message_index = segments.len();
+        let mut message = Segment::new(self.message)
+            .colour(self.colour.unwrap_or(Colour::from_colour_code(0x44_cc_11)))
+            .link_target(self.link_target);
+        message.link = self.message_link;
+        message.dark_colour = self.dark_colour;
+        message.animation = self.animation;
+        if let Some(progress) = self.progress {
+            message = message.progress(progress);
+        }
+        message.track_colour = self.track_colour;
+        message.dark_track_colour = self.dark_track_colour;
+        message.sparkline = self.sparkline;
+        if !self.cycle.is_empty() {
+            message = message.cycle(self.cycle, self.cycle_period);
+        }
+        segments.push(message);
+        segments.extend(self.segments);
+        if let Some(logo) = self.logo.filter(|l| !l.is_empty()) {
+            let (index, side) = match self.logo_position {
+                LogoPosition::Left => (0, LogoSide::Start),
+                LogoPosition::Message => (message_index, LogoSide::Start),
+                LogoPosition::Right => (segments.len() - 1, LogoSide::End),
+            };
+            let segment = &mut segments[index];
+            segment.logo = Some(logo);
+            segment.logo_width = self.logo_width;
+            segment.logo_side = side;
+        }
+        for segment in &mut segments {
+            segment.max_width = segment.max_width.or(self.max_width);
+            segment.max_chars = segment.max_chars.or(self.max_chars);
+            segment.multi_line |= self.multi_line;
+            segment.truncate(&self.geometry);
+        }
+        Badge {
+            segments,
+            id_prefix: self.id_prefix,
+            geometry: self.geometry,
+            style: self.style,
+        }
     }
 }
 
 impl<'a> Badge<'a> {
-    const HORIZ_PADDING: f32 = 5.0;
-
     #[must_use]
     /// Create a new [`Builder`] for a [`Badge`].
     ///
@@ -138,270 +632,903 @@ impl<'a> Badge<'a> {
         Builder::default()
     }
 
-    fn new(
-        label: Option<&'a str>,
-        message: &'a str,
-        logo: Option<&'a str>,
-        logo_width: Option<f32>,
-        colour: Option<Colour>,
-        label_colour: Option<Colour>,
-    ) -> Self {
-        let colour = colour.unwrap_or(Colour::from_colour_code(0x44_cc_11));
-        let label_colour = label_colour.unwrap_or(Colour::from_colour_code(0x55_55_55));
-        Self {
-            label_colour,
-            colour,
-            label,
-            message,
-            logo,
-            logo_width,
+    /// Create a [`Builder`] for a test coverage badge.
+    ///
+    /// The badge is labelled `coverage`, with the percentage rounded to a whole number as its
+    /// message, and is coloured by [`ColourScale::coverage`]. A percentage that is not a finite
+    /// number is shown as `unknown` in grey. Any of these can be changed on the returned builder.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use badges::{Badge, Render};
+    /// let badge = Badge::coverage(91.3).build();
+    /// assert!(badge.render().contains("<title>coverage: 91%</title>"));
+    /// ```
+    #[must_use]
+    pub fn coverage(percentage: f64) -> Builder<'a> {
+        let builder = Builder::default().label("coverage");
+        if !percentage.is_finite() {
+            return builder.message("unknown").colour(LIGHT_GREY);
         }
+        let percentage = percentage.round();
+        builder
+            .message(format::percent(percentage))
+            .colour(ColourScale::coverage().colour(percentage))
     }
 
-    const fn label_colour(&self) -> Colour {
-        if self.has_label() || self.has_logo() {
-            self.label_colour
-        } else {
-            self.colour
-        }
+    /// Create a [`Builder`] for a download count badge.
+    ///
+    /// The badge is labelled `downloads`, with the count shortened by [`format::metric`] as its
+    /// message, and is coloured by [`ColourScale::downloads`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use badges::{Badge, Render};
+    /// let badge = Badge::downloads(12_345).build();
+    /// assert!(badge.render().contains("<title>downloads: 12k</title>"));
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn downloads(downloads: u64) -> Builder<'a> {
+        let downloads = downloads as f64;
+        Builder::default()
+            .label("downloads")
+            .message(format::metric(downloads))
+            .colour(ColourScale::downloads().colour(downloads))
     }
-    const fn has_logo(&self) -> bool {
-        matches!(self.logo, Some(l) if !l.is_empty())
+
+    /// Create a [`Builder`] for a version badge.
+    ///
+    /// The badge is labelled `version`, with the version as formatted by [`format::version`] as
+    /// its message. It is orange for [pre-releases](format::is_prerelease) and blue otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use badges::{Badge, Render};
+    /// let badge = Badge::version("1.2.3").build();
+    /// assert!(badge.render().contains("<title>version: v1.2.3</title>"));
+    /// ```
+    #[must_use]
+    pub fn version(version: &str) -> Builder<'a> {
+        Builder::default()
+            .label("version")
+            .message(format::version(version))
+            .colour(format::version_colour(version))
     }
 
-    const fn has_label(&self) -> bool {
-        matches!(self.label, Some(l) if !l.is_empty())
+    /// Create a [`Builder`] for a size badge.
+    ///
+    /// The badge is labelled `size`, with the size formatted by [`format::bytes`] as its message,
+    /// and is blue.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use badges::{Badge, Render};
+    /// let badge = Badge::size(1536).build();
+    /// assert!(badge.render().contains("<title>size: 1.5 KiB</title>"));
+    /// ```
+    #[must_use]
+    pub fn size(bytes: u64) -> Builder<'a> {
+        Builder::default()
+            .label("size")
+            .message(format::bytes(bytes))
+            .colour(BLUE)
     }
 
-    const fn logo_width(&self) -> f32 {
-        match self.logo_width {
-            Some(w) => w,
-            None => {
-                if self.has_logo() {
-                    14.0
-                } else {
-                    0.0
-                }
-            }
+    /// Create a [`Builder`] for a progress bar badge, such as for coverage or a quota.
+    ///
+    /// The message is `fraction` as a percentage rounded to a whole number, drawn over a bar
+    /// filled for that fraction of its width and coloured by [`ColourScale::percentage`].
+    /// `fraction` is clamped to between `0.0` and `1.0`, and one that is not a finite number is
+    /// shown as `unknown` in grey with no bar.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use badges::{Badge, Render};
+    /// let badge = Badge::progress("quota", 0.42).build();
+    /// assert!(badge.render().contains("<title>quota: 42%</title>"));
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn progress(label: impl Into<Cow<'a, str>>, fraction: f64) -> Builder<'a> {
+        let builder = Builder::default().label(label);
+        if !fraction.is_finite() {
+            return builder.message("unknown").colour(LIGHT_GREY);
         }
+        let fraction = fraction.clamp(0.0, 1.0);
+        let percentage = (fraction * 100.0).round();
+        builder
+            .message(format::percent(percentage))
+            .colour(ColourScale::percentage().colour(percentage))
+            .progress(fraction as f32)
+    }
+
+    /// Create a [`Builder`] for a badge charting `values` over time, from oldest to newest.
+    ///
+    /// The message is the newest value shortened by [`format::metric`], followed by a
+    /// [`Sparkline`] of all of them, and the badge is blue. Use [`Builder::message`] to change
+    /// the text, or set it to `""` for the chart alone.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use badges::{Badge, Render};
+    /// let badge = Badge::sparkline("downloads", [1200.0, 1850.0, 2400.0]).build();
+    /// let svg = badge.render();
+    /// assert!(svg.contains("<title>downloads: 2.4k</title>"));
+    /// assert!(svg.contains("<path"));
+    /// ```
+    #[must_use]
+    pub fn sparkline(
+        label: impl Into<Cow<'a, str>>,
+        values: impl IntoIterator<Item = f64>,
+    ) -> Builder<'a> {
+        let sparkline = Sparkline::new(values);
+        let message = sparkline.latest().map_or_else(String::new, format::metric);
+        Builder::default()
+            .label(label)
+            .message(message)
+            .colour(BLUE)
+            .sparkline(sparkline)
     }
 
-    const fn logo_padding(&self) -> f32 {
-        if self.has_label() && self.has_logo() {
-            3.0
-        } else {
-            0.0
+    /// The text read out by screen readers, also used as the title of the badge.
+    pub(crate) fn accessible_text(&self) -> String {
+        match self.segments.split_first() {
+            Some((first, [])) => first.full_text().into_owned(),
+            Some((first, rest)) => {
+                let rest = rest.iter().map(Segment::full_text).join(", ");
+                format!("{first}: {rest}", first = first.full_text())
+            }
+            None => String::new(),
         }
     }
 
-    fn accessible_text(&self) -> String {
-        self.label.map_or_else(
-            || self.message.to_string(),
-            |label| format!("{label}: {message}", message = self.message),
+    /// The prefix of the ids of elements in the rendered SVG.
+    ///
+    /// Unless one is set, this is a hash of everything that changes how the badge is drawn.
+    pub(crate) fn id_prefix(&self) -> Cow<'a, str> {
+        self.id_prefix.map_or_else(
+            || {
+                let mut hasher = Fnv1a::default();
+                self.segments.hash(&mut hasher);
+                self.geometry.hash(&mut hasher);
+                self.style.hash(&mut hasher);
+                Cow::Owned(format!("b{:016x}", hasher.finish()))
+            },
+            Cow::Borrowed,
         )
     }
 
-    fn label_margin(&self) -> f32 {
-        self.logo_width() + self.logo_padding() + 1.0
+    /// Write the `<svg>` element for the badge, offset by `(x, y)` when it is nested in another
+    /// `<svg>`.
+    pub(crate) fn write_svg(
+        &self,
+        w: &mut Writer,
+        layout: &Layout,
+        ids: &Ids,
+        a11y_text: &str,
+        (x, y): (f32, f32),
+    ) -> fmt::Result {
+        let mut svg = w
+            .element("svg")
+            .attr("xmlns", "http://www.w3.org/2000/svg")
+            .attr("xmlns:xlink", "http://www.w3.org/1999/xlink")
+            .attr_float("width", layout.width())
+            .attr_float("height", layout.height())
+            .attr("role", "img")
+            .attr("aria-label", a11y_text);
+        if x != 0.0 {
+            svg = svg.attr_float("x", x);
+        }
+        if y != 0.0 {
+            svg = svg.attr_float("y", y);
+        }
+        svg.content(|w| {
+            w.element("title").text(a11y_text)?;
+            self.write_dark_style(w, ids)?;
+            if self.style.gradient {
+                w.element("linearGradient")
+                    .attr("id", &ids.gradient)
+                    .attr("x2", "0")
+                    .attr("y2", "100%")
+                    .content(|w| {
+                        w.element("stop")
+                            .attr("offset", "0")
+                            .attr("stop-color", "#bbb")
+                            .attr("stop-opacity", ".1")
+                            .empty()?;
+                        w.element("stop")
+                            .attr("offset", "1")
+                            .attr("stop-opacity", ".1")
+                            .empty()
+                    })?;
+            }
+            Self::write_clip_path(w, layout, &ids.clip, self.geometry.corner_radius)?;
+            self.write_background_group(w, layout, ids)?;
+            self.write_foreground_group(w, layout, ids)
+        })
     }
 
-    fn label_width(&self) -> f32 {
-        preferred_width(self.label.unwrap_or_default())
+    /// Compute the [`Layout`] of the badge.
+    ///
+    /// This measures the text of every segment, so the result should be kept around rather
+    /// than recomputed if it is needed more than once.
+    #[must_use]
+    pub fn layout(&self) -> Layout {
+        Layout::new(&self.segments, &self.geometry, self.style.shadow)
     }
 
-    fn left_width(&self) -> f32 {
-        if self.has_label() {
-            2.0f32.mul_add(Self::HORIZ_PADDING, self.label_width())
-                + self.logo_padding()
-                + self.logo_width()
-        } else {
-            0.0
+    fn write_logo(w: &mut Writer, segment: &Segment, layout: &SegmentLayout) -> fmt::Result {
+        match (segment.logo, layout.logo()) {
+            (Some(logo), Some(rect)) => w
+                .element("image")
+                .attr_float("x", rect.x())
+                .attr_float("y", rect.y())
+                .attr_float("width", rect.width())
+                .attr_float("height", rect.height())
+                .attr("xlink:href", logo)
+                .empty(),
+            _ => Ok(()),
         }
     }
 
-    #[allow(clippy::cast_precision_loss)]
-    fn message_margin(&self) -> f32 {
-        let mut mar = self.left_width() - self.message.len().min(1) as f32;
-        if !self.has_label() {
-            if self.has_logo() {
-                mar += self.logo_padding() + self.logo_width() + Self::HORIZ_PADDING;
-            } else {
-                mar += 1.0;
+    /// Start the `<text>` element for the lines of a segment, whose content is written by
+    /// [`Badge::write_lines`].
+    fn text_tag<'t, 'w>(
+        w: &'t mut Writer<'w>,
+        segment: &Segment,
+        text_box: TextBox,
+        fill: Colour,
+    ) -> Tag<'t, 'w> {
+        let text = w.element("text");
+        if segment.line_count() > 1 {
+            return text
+                .attr("transform", FONT_SCALE_DOWN_VALUE)
+                .attr("fill", fill);
+        }
+        text.attr_float("x", FONT_SCALE_UP_FACTOR * text_box.x())
+            .attr_float("y", FONT_SCALE_UP_FACTOR * text_box.y())
+            .attr("transform", FONT_SCALE_DOWN_VALUE)
+            .attr("fill", fill)
+            .attr_float("textLength", FONT_SCALE_UP_FACTOR * text_box.text_length())
+    }
+
+    /// Write the lines of a segment, with one `<tspan>` per line if there are several.
+    fn write_lines(
+        w: &mut Writer,
+        segment: &Segment,
+        geometry: &Geometry,
+        text_box: TextBox,
+    ) -> fmt::Result {
+        if segment.line_count() == 1 {
+            return w.text(&segment.text);
+        }
+        for (index, line) in segment.lines().enumerate() {
+            if line.is_empty() {
+                continue;
             }
+            let line_box = text_box.line(index, line, geometry);
+            w.element("tspan")
+                .attr_float("x", FONT_SCALE_UP_FACTOR * line_box.x())
+                .attr_float("y", FONT_SCALE_UP_FACTOR * line_box.y())
+                .attr_float("textLength", FONT_SCALE_UP_FACTOR * line_box.text_length())
+                .text(line)?;
         }
-        mar
+        Ok(())
     }
 
-    fn message_width(&self) -> f32 {
-        preferred_width(self.message)
+    fn write_text(
+        &self,
+        w: &mut Writer,
+        segment: &Segment,
+        layout: &SegmentLayout,
+        dark_class: Option<(&Ids, usize)>,
+    ) -> fmt::Result {
+        let Some(text_box) = layout.text() else {
+            return Ok(());
+        };
+        match segment.animation {
+            Some(Animation::Blink { period }) => w.element("g").content(|w| {
+                animation::animate(w, "opacity", &"1;0", period)
+                    .attr("calcMode", "discrete")
+                    .empty()?;
+                self.write_frames(w, segment, layout, text_box, dark_class)
+            }),
+            _ => self.write_frames(w, segment, layout, text_box, dark_class),
+        }
     }
 
-    fn right_width(&self) -> f32 {
-        let mut width = 2.0f32.mul_add(Self::HORIZ_PADDING, self.message_width());
-        if self.has_logo() && !self.has_label() {
-            width += self.logo_padding() + self.logo_width() + Self::HORIZ_PADDING - 1.0;
+    /// Write the text of a segment, or each of the texts it cycles through with the animation
+    /// switching between them.
+    fn write_frames(
+        &self,
+        w: &mut Writer,
+        segment: &Segment,
+        layout: &SegmentLayout,
+        text_box: TextBox,
+        dark_class: Option<(&Ids, usize)>,
+    ) -> fmt::Result {
+        if segment.cycle.is_empty() {
+            return self.write_frame(w, segment, text_box, layout.shadow(), dark_class);
+        }
+        let count = segment.cycle.len() + 1;
+        for (index, frame) in segment.frames().enumerate() {
+            let first_line = frame.lines().next().unwrap_or_default();
+            let text_box = text_box.line(0, first_line, &self.geometry);
+            let shadow_box = layout
+                .shadow()
+                .map(|shadow_box| shadow_box.line(0, first_line, &self.geometry));
+            let mut group = w.element("g");
+            // only the first frame shows where animations are not supported
+            if index > 0 {
+                group = group.attr("opacity", "0");
+            }
+            group.content(|w| {
+                animation::cycle(w, index, count, segment.cycle_period)?;
+                self.write_frame(w, frame, text_box, shadow_box, dark_class)
+            })?;
         }
-        width
+        Ok(())
     }
 
-    fn width(&self) -> f32 {
-        self.left_width() + self.right_width()
+    /// Write the text of a segment and the shadow behind it, at `text_box` and `shadow_box`.
+    fn write_frame(
+        &self,
+        w: &mut Writer,
+        segment: &Segment,
+        text_box: TextBox,
+        shadow_box: Option<TextBox>,
+        dark_class: Option<(&Ids, usize)>,
+    ) -> fmt::Result {
+        let geometry = &self.geometry;
+        let (text_colour, shadow_colour) = text_colours(segment, false);
+        if let Some(shadow_box) = shadow_box {
+            let shadow_colour = self.style.shadow_colour.unwrap_or(shadow_colour);
+            let mut shadow =
+                Self::text_tag(w, segment, shadow_box, shadow_colour).attr("aria-hidden", "true");
+            shadow = match self.style.shadow_opacity {
+                Some(opacity) => shadow.attr_float("fill-opacity", opacity),
+                None => shadow.attr("fill-opacity", ".3"),
+            };
+            if let Some((ids, index)) = dark_class {
+                shadow = shadow.attr_display("class", &ids.dark_class(index, "h"));
+            }
+            shadow.content(|w| Self::write_lines(w, segment, geometry, shadow_box))?;
+        }
+        let mut text = Self::text_tag(w, segment, text_box, text_colour);
+        if let Some((ids, index)) = dark_class {
+            text = text.attr_display("class", &ids.dark_class(index, "t"));
+        }
+        text.content(|w| Self::write_lines(w, segment, geometry, text_box))
     }
 
-    fn logo_element(&self) -> Content<'a> {
-        self.logo.map_or_else(Content::default, |logo| {
-            Content::Element(
-                Element::new("image")
-                    .attr_float("x", Self::HORIZ_PADDING)
-                    .attr_float("y", 0.5 * (HEIGHT - LOGO_HEIGHT))
-                    .attr_float("width", self.logo_width())
-                    .attr_float("height", LOGO_HEIGHT)
-                    .attr("xlink:href", logo),
-            )
+    fn write_clip_path(w: &mut Writer, layout: &Layout, id: &str, rx: f32) -> fmt::Result {
+        w.element("clipPath").attr("id", id).content(|w| {
+            w.element("rect")
+                .attr_float("width", layout.width())
+                .attr_float("height", layout.height())
+                .attr_float("rx", rx)
+                .attr("fill", "#fff")
+                .empty()
         })
     }
 
-    fn _text_element(content: &'a str, colour: Colour, width: f32, margin: f32) -> Content<'a> {
-        if content.is_empty() {
-            return Content::default();
+    /// The CSS switching segments to their dark colours when the viewer prefers a dark colour
+    /// scheme, if any segment has one.
+    fn dark_css(&self, ids: &Ids) -> Option<String> {
+        if !self.segments.iter().any(Segment::has_dark_colours) {
+            return None;
         }
-        let (text_colour, shadow_colour) = colours_for_background(colour);
-        let x = FONT_SCALE_UP_FACTOR * (0.5f32.mul_add(width, margin) + Self::HORIZ_PADDING);
-        let text = Element::new("text")
-            .content(vec![Content::Text(content)])
-            .attr_float("x", x)
-            .attr_float("y", 140.0 + VERTICAL_MARGIN)
-            .attr("transform", FONT_SCALE_DOWN_VALUE)
-            .attr("fill", text_colour.to_string())
-            .attr_float("textLength", FONT_SCALE_UP_FACTOR * width);
-        let shadow_text = Element::new("text")
-            .content(vec![Content::Text(content)])
-            .attr("aria-hidden", "true")
-            .attr_float("x", x)
-            .attr_float("y", 150.0 + VERTICAL_MARGIN)
-            .attr("transform", FONT_SCALE_DOWN_VALUE)
-            .attr("fill", shadow_colour.to_string())
-            .attr("fill-opacity", ".3")
-            .attr_float("textLength", FONT_SCALE_UP_FACTOR * width);
-        let shadow = if SHADOW {
-            Content::Element(shadow_text)
-        } else {
-            Content::default()
-        };
-        Content::List(ElementList::new(vec![shadow, Content::Element(text)]))
+        let mut css = String::from("@media (prefers-color-scheme:dark){");
+        for (index, segment) in self.segments.iter().enumerate() {
+            if !segment.has_dark_colours() {
+                continue;
+            }
+            let mut rule = |part, colour| {
+                write!(css, ".{}{{fill:{colour}}}", ids.dark_class(index, part)).expect(":derp:");
+            };
+            if let Some(dark_colour) = segment.dark_colour {
+                rule("", dark_colour);
+            }
+            if let (Some(_), Some(dark_track_colour)) =
+                (segment.progress, segment.dark_track_colour)
+            {
+                rule("k", dark_track_colour);
+            }
+            let (text_colour, shadow_colour) = text_colours(segment, true);
+            rule("t", text_colour);
+            if self.style.shadow_colour.is_none() {
+                rule("h", shadow_colour);
+            }
+        }
+        css.push('}');
+        Some(css)
     }
 
-    fn label_element(&self) -> Content<'a> {
-        Self::_text_element(
-            self.label.unwrap_or_default(),
-            self.label_colour,
-            self.label_width(),
-            self.label_margin(),
-        )
+    /// Write a `<style>` element with the [dark CSS](Self::dark_css) of the badge, if it has any.
+    fn write_dark_style(&self, w: &mut Writer, ids: &Ids) -> fmt::Result {
+        self.dark_css(ids)
+            .map_or(Ok(()), |css| w.element("style").content(|w| w.cdata(&css)))
     }
 
-    fn message_element(&self) -> Content<'a> {
-        Self::_text_element(
-            self.message,
-            self.colour,
-            self.message_width(),
-            self.message_margin(),
-        )
+    fn write_background_group(&self, w: &mut Writer, layout: &Layout, ids: &Ids) -> fmt::Result {
+        w.element("g")
+            .attr_url("clip-path", &ids.clip)
+            .content(|w| {
+                for (index, (segment, segment_layout)) in
+                    self.segments.iter().zip(layout.segments()).enumerate()
+                {
+                    Self::write_background(w, segment, segment_layout.rect(), ids, index)?;
+                }
+                if self.style.gradient {
+                    w.element("rect")
+                        .attr_float("width", layout.width())
+                        .attr_float("height", layout.height())
+                        .attr_url("fill", &ids.gradient)
+                        .empty()?;
+                }
+                Ok(())
+            })
     }
 
-    fn clip_path(&self, rx: f32) -> Content<'a> {
-        Content::Element(
-            Element::new("clipPath")
-                .content(vec![Content::Element(
-                    Element::new("rect")
-                        .attr_float("width", self.width())
-                        .attr_float("height", HEIGHT)
-                        .attr_float("rx", rx)
-                        .attr("fill", "#fff"),
-                )])
-                .attr("id", "r"),
-        )
+    /// Write the background of the segment at `index`, which covers `bounds`.
+    fn write_background(
+        w: &mut Writer,
+        segment: &Segment,
+        bounds: Rect,
+        ids: &Ids,
+        index: usize,
+    ) -> fmt::Result {
+        let mut width = bounds.width();
+        if let Some(progress) = segment.progress {
+            // the unfilled track, which the bar is drawn over
+            let mut track = w.element("rect");
+            if bounds.x() > 0.0 {
+                track = track.attr_float("x", bounds.x());
+            }
+            track = track
+                .attr_float("width", width)
+                .attr_float("height", bounds.height())
+                .attr("fill", segment.track_colour.unwrap_or(LIGHT_GREY));
+            if segment.dark_track_colour.is_some() {
+                track = track.attr_display("class", &ids.dark_class(index, "k"));
+            }
+            track.empty()?;
+            width *= progress;
+        }
+        let mut rect = w.element("rect");
+        if bounds.x() > 0.0 {
+            rect = rect.attr_float("x", bounds.x());
+        }
+        rect = rect
+            .attr_float("width", width)
+            .attr_float("height", bounds.height())
+            .attr("fill", segment.colour);
+        if segment.dark_colour.is_some() {
+            rect = rect.attr_display("class", &ids.dark_class(index, ""));
+        }
+        match segment.animation {
+            Some(Animation::Pulse { colour, period }) => rect.content(|w| {
+                let values = format_args!("{base};{colour};{base}", base = segment.colour);
+                animation::animate(w, "fill", &values, period).empty()
+            }),
+            _ => rect.empty(),
+        }
     }
 
-    fn background_group_element(&self, gradient: bool) -> Element {
-        let left_rect = Element::new("rect")
-            .attr_float("width", self.left_width())
-            .attr_float("height", HEIGHT)
-            .attr("fill", self.label_colour().to_string());
-        let right_rect = Element::new("rect")
-            .attr_float("x", self.left_width())
-            .attr_float("width", self.right_width())
-            .attr_float("height", HEIGHT)
-            .attr("fill", self.colour.to_string());
-        let mut content = vec![Content::Element(left_rect), Content::Element(right_rect)];
-        if gradient {
-            let grad = Element::new("rect")
-                .attr_float("width", self.width())
-                .attr_float("height", HEIGHT)
-                .attr("fill", "url(#s)");
-            content.push(Content::Element(grad));
-        }
-        Element::new("g").content(content)
-    }
-
-    fn foreground_group_element(&self) -> Content<'a> {
-        Content::Element(
-            Element::new("g")
-                .content(vec![
-                    self.logo_element(),
-                    self.label_element(),
-                    self.message_element(),
-                ])
-                .attr("fill", "#fff")
-                .attr("text-anchor", "middle")
-                .attr("font-family", FONT_FAMILY)
-                .attr("text-rendering", "geometricPrecision")
-                .attr("font-size", "110"),
-        )
+    /// Write the foreground of a segment written by `content`, wrapped in an `<a>` element
+    /// along with a transparent `rect` covering the whole segment so that its background is
+    /// clickable too.
+    fn write_link(
+        w: &mut Writer,
+        segment: &Segment,
+        layout: &SegmentLayout,
+        content: impl FnOnce(&mut Writer) -> fmt::Result,
+    ) -> fmt::Result {
+        let Some(link) = segment.link else {
+            return content(w);
+        };
+        let mut anchor = w.element("a").attr("target", segment.link_target.as_str());
+        if segment.link_target == LinkTarget::Blank {
+            anchor = anchor.attr("rel", "noopener noreferrer");
+        }
+        anchor.attr("xlink:href", link).content(|w| {
+            let mut rect = w.element("rect");
+            let bounds = layout.rect();
+            if bounds.x() > 0.0 {
+                rect = rect.attr_float("x", bounds.x());
+            }
+            rect.attr_float("width", bounds.width())
+                .attr_float("height", bounds.height())
+                .attr("fill", "rgba(0,0,0,0)")
+                .empty()?;
+            content(w)
+        })
+    }
+
+    fn write_foreground_group(&self, w: &mut Writer, layout: &Layout, ids: &Ids) -> fmt::Result {
+        w.element("g")
+            .attr("fill", "#fff")
+            .attr("text-anchor", "middle")
+            .attr("font-family", self.style.font_family.unwrap_or(FONT_FAMILY))
+            .attr("text-rendering", "geometricPrecision")
+            .attr_display(
+                "font-size",
+                &(FONT_SCALE_UP_FACTOR * self.geometry.font_size),
+            )
+            .content(|w| {
+                for (index, (segment, segment_layout)) in
+                    self.segments.iter().zip(layout.segments()).enumerate()
+                {
+                    Self::write_link(w, segment, segment_layout, |w| {
+                        if segment.has_logo() {
+                            Self::write_logo(w, segment, segment_layout)?;
+                        }
+                        if let (Some(sparkline), Some(chart)) =
+                            (&segment.sparkline, segment_layout.chart())
+                        {
+                            let (text_colour, _) = text_colours(segment, false);
+                            sparkline.write(w, chart, text_colour)?;
+                        }
+                        let dark_class = segment.has_dark_colours().then_some((ids, index));
+                        self.write_text(w, segment, segment_layout, dark_class)
+                    })?;
+                }
+                Ok(())
+            })
     }
 }
 
 impl<'a> Render<'a> for Badge<'a> {
     fn render(&self) -> Cow<'a, str> {
-        let gradient = Element::new("linearGradient")
-            .content(vec![
-                Content::Element(
-                    Element::new("stop")
-                        .attr("offset", "0")
-                        .attr("stop-color", "#bbb")
-                        .attr("stop-opacity", ".1"),
-                ),
-                Content::Element(
-                    Element::new("stop")
-                        .attr("offset", "1")
-                        .attr("stop-opacity", ".1"),
-                ),
-            ])
-            .attr("id", "s")
-            .attr("x2", "0")
-            .attr("y2", "100%");
-        let clip_path = self.clip_path(3.0);
-        let background_group = self
-            .background_group_element(true)
-            .attr("clip-path", "url(#r)");
-        let body = Content::List(ElementList::new(vec![
-            Content::Element(gradient),
-            clip_path,
-            Content::Element(background_group),
-            self.foreground_group_element(),
-        ]));
-        let width = self.left_width() + self.right_width();
+        traits::render_string(self)
+    }
+
+    fn render_to(&self, w: &mut dyn Write) -> fmt::Result {
+        let layout = self.layout();
+        let ids = Ids::new(&self.id_prefix());
         let a11y_text = self.accessible_text();
-        let title =
-            Content::Element(Element::new("title").content(vec![Content::Text(&a11y_text)]));
-        let svg = Element::new("svg")
-            .content(vec![title, body])
-            .attr("xmlns", "http://www.w3.org/2000/svg")
-            .attr("xmlns:xlink", "http://www.w3.org/1999/xlink")
-            .attr_float("width", width)
-            .attr_float("height", HEIGHT)
-            .attr("role", "img")
-            .attr("aria-label", self.accessible_text());
-        Cow::Owned(svg.render().into_owned())
+        self.write_svg(&mut Writer::new(w), &layout, &ids, &a11y_text, (0.0, 0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logo_x(badge: &Badge) -> f32 {
+        badge
+            .layout()
+            .segments()
+            .iter()
+            .find_map(SegmentLayout::logo)
+            .map(|logo| logo.x())
+            .expect("badge has no logo")
+    }
+
+    #[test]
+    fn test_logo_position_keeps_width() {
+        let builder = Badge::builder().label("build").message("passing").logo("x");
+        let left = builder.clone().build();
+        let message = builder.clone().logo_position(LogoPosition::Message).build();
+        let right = builder.logo_position(LogoPosition::Right).build();
+        assert!((left.layout().width() - 105.45).abs() < f32::EPSILON);
+        assert!((left.layout().width() - message.layout().width()).abs() < f32::EPSILON);
+        assert!((left.layout().width() - right.layout().width()).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_logo_position_placement() {
+        let builder = Badge::builder().label("build").message("passing").logo("x");
+        let left = builder.clone().build();
+        assert!((logo_x(&left) - 5.0).abs() < f32::EPSILON);
+        let message = builder.clone().logo_position(LogoPosition::Message).build();
+        assert!((logo_x(&message) - 41.7).abs() < f32::EPSILON);
+        let right = builder.logo_position(LogoPosition::Right).build();
+        assert!((logo_x(&right) - (right.layout().width() - 19.0)).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_logo_without_text() {
+        for position in [LogoPosition::Left, LogoPosition::Right] {
+            let badge = Badge::builder().logo("x").logo_position(position).build();
+            assert!((badge.layout().width() - 28.0).abs() < f32::EPSILON);
+        }
+        let badge = Badge::builder().message("passing").logo("x").build();
+        let layout = badge.layout();
+        assert!((layout.width() - 69.75).abs() < f32::EPSILON);
+        let text = layout.segments()[0].text().expect("segment has no text");
+        assert!((text.x() - 43.875).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_segments() {
+        let badge = Badge::builder()
+            .label("build")
+            .message("passing")
+            .segment(Segment::new("2m 31s"))
+            .build();
+        assert_eq!(badge.segments.len(), 3);
+        assert_eq!(badge.accessible_text(), "build: passing, 2m 31s");
+        let two = Badge::builder().label("build").message("passing").build();
+        let extra = badge.layout().segments()[2].rect().width();
+        assert!((badge.layout().width() - two.layout().width() - extra).abs() < 1e-3);
+        let rects = badge.render().matches("<rect").count();
+        // one per segment, plus the clip path and the gradient overlay
+        assert_eq!(rects, 5);
+    }
+
+    #[test]
+    fn test_links() {
+        let badge = Badge::builder()
+            .label("build")
+            .message("passing")
+            .label_link("https://ci.example.com/?a=1&b=2")
+            .message_link("https://ci.example.com/runs/1")
+            .build();
+        let svg = badge.render();
+        assert_eq!(svg.matches("<a ").count(), 2);
+        assert!(svg.contains(r#"xlink:href="https://ci.example.com/?a=1&amp;b=2""#));
+        assert!(svg.contains(r#"<a target="_blank" rel="noopener noreferrer""#));
+        assert!(
+            svg.contains(r#"<rect x="36.7" width="51.75" height="20.0" fill="rgba(0,0,0,0)"/>"#)
+        );
+        let top = Badge::builder()
+            .message("passing")
+            .message_link("https://ci.example.com")
+            .link_target(LinkTarget::Top)
+            .build()
+            .render();
+        assert!(top.contains(r#"<a target="_top" xlink:href="https://ci.example.com">"#));
+    }
+
+    #[test]
+    fn test_id_prefix() {
+        let builder = Badge::builder().label("build").message("passing");
+        let passing = builder.clone().build().render();
+        let failing = builder.clone().message("failing").build().render();
+        let id = |svg: &str| {
+            let start = svg.find("<linearGradient id=\"").expect("no gradient") + 21;
+            svg[start..].split('"').next().map(ToOwned::to_owned)
+        };
+        assert_eq!(id(&passing), id(&builder.clone().build().render()));
+        assert_ne!(id(&passing), id(&failing));
+        // badges differing only in style have different CSS, so they need different ids
+        let styled = builder.clone().shadow_colour(Colour::RED).build();
+        let arial = builder.clone().font_family("Arial").build();
+        assert_ne!(id(&passing), id(&styled.render()));
+        assert_ne!(styled.id_prefix(), arial.id_prefix());
+        let custom = builder.clone().id_prefix("build").build().render();
+        assert!(custom.contains(r#"<linearGradient id="build-s""#));
+        assert!(custom.contains(r#"<clipPath id="build-r">"#));
+        assert!(custom.contains(r#"fill="url(#build-s)""#));
+        assert!(custom.contains(r#"clip-path="url(#build-r)""#));
+        assert!(is_valid_id_prefix("_readme-badge_2"));
+        // prefixes that would need escaping in ids or CSS selectors are not allowed
+        for invalid in ["", "a b", "x{}", "1st", "a.b"] {
+            assert!(!is_valid_id_prefix(invalid), "{invalid}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid id prefix `a b`")]
+    fn test_invalid_id_prefix() {
+        let _ = Badge::builder().message("passing").id_prefix("a b");
+    }
+
+    #[test]
+    fn test_style() {
+        let builder = Badge::builder().label("build").message("passing");
+        let flat = builder.clone().shadow(false).gradient(false).build();
+        assert!(flat
+            .layout()
+            .segments()
+            .iter()
+            .all(|s| s.shadow().is_none()));
+        let svg = flat.render();
+        assert!(!svg.contains("linearGradient") && !svg.contains("-s)"));
+        assert!(svg.contains("clip-path="));
+        assert_eq!(svg.matches("<text").count(), 2);
+
+        let svg = builder
+            .shadow_colour(Colour::RED)
+            .shadow_opacity(0.5)
+            .build()
+            .render();
+        assert_eq!(svg.matches(r##"fill="#ff0000""##).count(), 2);
+        assert_eq!(
+            svg.matches(r#"aria-hidden="true" fill-opacity="0.5""#)
+                .count(),
+            2
+        );
+        assert!(svg.contains("<linearGradient"));
+    }
+
+    #[test]
+    #[allow(clippy::literal_string_with_formatting_args)]
+    fn test_dark_colours() {
+        let badge = Badge::builder()
+            .label("build")
+            .message("passing")
+            .dark_colour(Colour::from_colour_code(0xdd_dd_dd))
+            .id_prefix("x")
+            .build();
+        let svg = badge.render();
+        assert!(svg.contains(
+            "<style><![CDATA[@media (prefers-color-scheme:dark){\
+             .x-1{fill:#dddddd}.x-1t{fill:#333333}.x-1h{fill:#cccccc}}]]></style>"
+        ));
+        assert_eq!(svg.matches(r#"class="x-1"#).count(), 3);
+        assert!(!svg.contains("x-0"));
+
+        let plain = Badge::builder().label("build").message("passing").build();
+        assert!(!plain.render().contains("<style>"));
+        assert_ne!(plain.id_prefix(), {
+            let dark = Badge::builder()
+                .label("build")
+                .message("passing")
+                .dark_colour(Colour::BLACK)
+                .build();
+            dark.id_prefix().into_owned()
+        });
+    }
+
+    #[test]
+    fn test_animation() {
+        let period = Duration::from_millis(1500);
+        let pulse = Badge::builder()
+            .message("failing")
+            .colour(Colour::from_colour_code(0xe0_5d_44))
+            .animation(Animation::Pulse {
+                colour: Colour::from_colour_code(0xff_ff_ff),
+                period,
+            })
+            .build();
+        assert!(pulse.render().contains(
+            r##"<animate attributeName="fill" values="#e05d44;#ffffff;#e05d44" dur="1.5s" repeatCount="indefinite"/>"##
+        ));
+
+        let blink = Badge::builder()
+            .message("failing")
+            .animation(Animation::Blink { period })
+            .build();
+        let svg = blink.render();
+        assert_eq!(svg.matches("<animate").count(), 1);
+        assert!(
+            svg.contains(r#"values="1;0" dur="1.5s" repeatCount="indefinite" calcMode="discrete""#)
+        );
+
+        let plain = Badge::builder().message("failing").build();
+        assert!(!plain.render().contains("<animate"));
+        assert_ne!(plain.id_prefix(), blink.id_prefix());
+    }
+
+    #[test]
+    fn test_cycle() {
+        let badge = Badge::builder()
+            .label("msrv")
+            .message("1.70")
+            .cycle(["1.74", "a much longer message"], Duration::from_secs(2))
+            .max_chars(8)
+            .build();
+        let svg = badge.render();
+        assert!(svg.contains("<title>msrv: 1.70 / 1.74 / a much longer message</title>"));
+        assert!(svg.contains(r#"keyTimes="0;0.33333334" calcMode="discrete""#));
+        assert!(svg.contains(r#"values="0;1;0" dur="6s""#));
+        assert_eq!(svg.matches(r#"<g opacity="0">"#).count(), 2);
+        // the message is sized for the widest, truncated message
+        let widest = Badge::builder()
+            .label("msrv")
+            .message("a much\u{2026}")
+            .build();
+        assert!((badge.layout().width() - widest.layout().width()).abs() < 1e-3);
+        // the total period saturates instead of overflowing
+        let forever = Badge::builder()
+            .message("a")
+            .cycle(["b"], Duration::MAX)
+            .build();
+        assert!(forever.render().contains(r#"values="0;1;0" dur=""#));
+    }
+
+    #[test]
+    fn test_progress() {
+        let badge = Badge::progress("quota", 0.25).id_prefix("x").build();
+        let svg = badge.render();
+        let message = badge.layout().segments()[1].rect();
+        assert!(svg.contains(&format!(
+            r##"<rect x="{x}" width="{width}" height="20.0" fill="#9f9f9f"/><rect x="{x}" width="{bar}" height="20.0" fill="#"##,
+            x = message.x(),
+            width = message.width(),
+            bar = message.width() * 0.25,
+        )));
+        assert!(svg.contains("<title>quota: 25%</title>"));
+
+        let nan = Badge::builder()
+            .label("quota")
+            .message("x")
+            .progress(f32::NAN)
+            .build();
+        assert_eq!(nan.segments[1].progress, None);
+        assert!(!nan.render().contains("e38"));
+        let full = Badge::builder()
+            .message("x")
+            .progress(f32::INFINITY)
+            .build();
+        assert_eq!(full.segments[0].progress, Some(1.0));
+
+        let unknown = Badge::progress("quota", f64::NAN).build();
+        assert!(unknown.render().contains("<title>quota: unknown</title>"));
+        let plain = Badge::builder().label("quota").message("unknown").build();
+        assert_eq!(
+            unknown.render().matches("<rect").count(),
+            plain.render().matches("<rect").count()
+        );
+    }
+
+    #[test]
+    fn test_progress_dark_track() {
+        let badge = Badge::progress("quota", 0.25)
+            .track_colour(Colour::WHITE)
+            .dark_track_colour(Colour::BLACK)
+            .id_prefix("x")
+            .build();
+        let svg = badge.render();
+        assert!(svg.contains(r##"fill="#ffffff" class="x-1k"/>"##));
+        assert!(svg.contains(&format!(".x-1k{{fill:{}}}", Colour::BLACK)));
+        assert!(svg.contains(r#"class="x-1t""#));
+        // the bar keeps its colour, so it has no dark rule of its own
+        assert!(!svg.contains(".x-1{"));
+        assert_ne!(
+            badge.id_prefix(),
+            Badge::progress("quota", 0.25)
+                .track_colour(Colour::WHITE)
+                .build()
+                .id_prefix()
+        );
+    }
+
+    #[test]
+    fn test_colours_for_backgrounds() {
+        let white = Colour::from_colour_code(0xff_ff_ff);
+        let dark_grey = Colour::from_colour_code(0x33_33_33);
+        let light = LIGHT_GREY;
+        assert_eq!(colours_for_backgrounds(Colour::BLACK, light).0, white);
+        assert_eq!(colours_for_backgrounds(Colour::WHITE, light).0, dark_grey);
+        assert_eq!(
+            colours_for_backgrounds(Colour::BLACK, Colour::BLACK),
+            colours_for_background(Colour::BLACK)
+        );
+    }
+
+    #[test]
+    fn test_coverage() {
+        let badge = Badge::coverage(89.6).build();
+        assert_eq!(badge.segments[1].text, "90%");
+        assert_eq!(
+            badge.segments[1].colour,
+            ColourScale::coverage().colour(90.0)
+        );
+        assert_eq!(Badge::coverage(-0.3).build().segments[1].text, "0%");
+        let badge = Badge::coverage(f64::NAN).label("tests").build();
+        assert_eq!(badge.accessible_text(), "tests: unknown");
+    }
+
+    #[test]
+    fn test_right_logo_on_last_segment() {
+        let badge = Badge::builder()
+            .label("coverage")
+            .message("91%")
+            .segment(Segment::new("+2.1%"))
+            .logo("x")
+            .logo_position(LogoPosition::Right)
+            .build();
+        assert!(!badge.segments[1].has_logo());
+        assert_eq!(badge.segments[2].logo_side, LogoSide::End);
     }
 }
@@ -0,0 +1,68 @@
+#![forbid(unsafe_code)]
+#![deny(
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::unwrap_in_result,
+    clippy::unwrap_used
+)]
+
+//! Serve badges over HTTP from shields.io style URLs.
+
+use std::{path::PathBuf, process::ExitCode};
+
+use badges::{DirectoryProvider, MemoryProvider, Server, ServerConfig};
+use clap::Parser;
+
+/// Serve badges over HTTP from shields.io style URLs.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Args {
+    /// The address to listen on.
+    #[arg(short, long, default_value = "127.0.0.1:8080")]
+    bind: String,
+    /// A host `/endpoint` badges may be fetched from, optionally with a port. May be repeated.
+    #[arg(long = "allow-host", value_name = "HOST")]
+    allowed_hosts: Vec<String>,
+    /// How long clients may cache badges for, in seconds.
+    #[arg(long, default_value_t = 300)]
+    cache_seconds: u32,
+    /// Serve the endpoint JSON files in this directory under `/b/`. They cannot be changed by
+    /// `POST`s.
+    #[arg(long, value_name = "DIR", conflicts_with = "memory")]
+    badge_dir: Option<PathBuf>,
+    /// Serve badges kept in memory under `/b/`, which start empty and are set by `POST`s.
+    #[arg(long)]
+    memory: bool,
+    /// The token `POST`s updating badges must send as `Authorization: Bearer <token>`.
+    #[arg(long, env = "BADGES_UPDATE_TOKEN", hide_env_values = true)]
+    update_token: Option<String>,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let mut config = args
+        .allowed_hosts
+        .into_iter()
+        .fold(ServerConfig::new(), ServerConfig::allow_host)
+        .cache_seconds(args.cache_seconds);
+    if let Some(dir) = args.badge_dir {
+        config = config.provider(DirectoryProvider::new(dir));
+    } else if args.memory {
+        config = config.provider(MemoryProvider::new());
+    }
+    if let Some(token) = args.update_token {
+        config = config.update_token(token);
+    }
+    let server = match Server::bind(&args.bind, config) {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("error: failed to listen on {}: {e}", args.bind);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(addr) = server.local_addr() {
+        eprintln!("listening on http://{addr}");
+    }
+    server.run();
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,296 @@
+//! The `batch` subcommand, which generates every badge described in a manifest.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use badges::Render;
+use clap::ValueEnum;
+use serde::{
+    de::{
+        self,
+        value::{Error as ValueError, MapDeserializer},
+        IntoDeserializer, Visitor,
+    },
+    forward_to_deserialize_any, Deserialize, Deserializer,
+};
+use serde_json::value::RawValue;
+
+use crate::BadgeArgs;
+
+/// Generate every badge described in a manifest.
+///
+/// A TOML manifest has a `[[badge]]` table per badge, a JSON manifest is an array of objects,
+/// and a CSV manifest has a row per badge with a header naming the columns. The fields are named
+/// after the long command line options, e.g. `label_colour`, and every badge needs an `output`.
+///
+/// In CSV manifests, empty cells are left unset, and lists such as `segments` are written as a
+/// single cell of items separated by `|`.
+#[derive(Debug, clap::Args)]
+pub struct BatchArgs {
+    /// The manifest describing the badges.
+    manifest: PathBuf,
+    /// The format of the manifest. Inferred from its extension by default.
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+    /// The directory outputs are written to. Defaults to the directory of the manifest.
+    #[arg(long)]
+    out_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    Toml,
+    Json,
+    Csv,
+}
+
+impl Format {
+    fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "toml" => Some(Self::Toml),
+            "json" => Some(Self::Json),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// A badge described by a manifest, or why it could not be read.
+struct Entry {
+    /// The line of the manifest to report errors at.
+    line: u64,
+    badge: Result<BadgeArgs, String>,
+}
+
+enum Status {
+    Written,
+    Unchanged,
+}
+
+/// The line of `source` that the byte at `offset` is on, counting from 1.
+fn line_of(source: &str, offset: usize) -> u64 {
+    source.get(..offset).unwrap_or(source).matches('\n').count() as u64 + 1
+}
+
+fn parse_toml(source: &str) -> Result<Vec<Entry>, String> {
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Manifest {
+        #[serde(default)]
+        badge: Vec<toml::Spanned<toml::Value>>,
+    }
+    let manifest: Manifest = toml::from_str(source).map_err(|e| e.to_string())?;
+    Ok(manifest
+        .badge
+        .into_iter()
+        .map(|badge| Entry {
+            line: line_of(source, badge.span().start),
+            badge: badge
+                .into_inner()
+                .try_into()
+                .map_err(|e: toml::de::Error| e.message().to_owned()),
+        })
+        .collect())
+}
+
+fn parse_json(source: &str) -> Result<Vec<Entry>, String> {
+    let badges: Vec<&RawValue> = serde_json::from_str(source).map_err(|e| e.to_string())?;
+    Ok(badges
+        .into_iter()
+        .map(|badge| {
+            // the raw value borrows from the source, so its position is where the entry starts
+            let offset = badge.get().as_ptr() as usize - source.as_ptr() as usize;
+            let line = line_of(source, offset);
+            match serde_json::from_str(badge.get()) {
+                Ok(badge) => Entry {
+                    line,
+                    badge: Ok(badge),
+                },
+                Err(e) => {
+                    let message = e.to_string();
+                    let message = message
+                        .rsplit_once(" at line ")
+                        .map_or(message.as_str(), |(message, _)| message);
+                    Entry {
+                        line: line + e.line() as u64 - 1,
+                        badge: Err(message.to_owned()),
+                    }
+                }
+            }
+        })
+        .collect())
+}
+
+/// A cell of a CSV manifest, read as whatever type its column needs.
+///
+/// The `csv` crate guesses the type of a cell when a field accepts several, so that a segment of
+/// `42` would be read as a number rather than text.
+struct Cell<'a>(&'a str);
+
+/// Deserialize a cell by parsing it as the number or `bool` the field needs.
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+                let value = self
+                    .0
+                    .parse()
+                    .map_err(|e| de::Error::custom(format!("invalid value `{}`: {e}", self.0)))?;
+                visitor.$visit(value)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Cell<'de> {
+    type Error = ValueError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        visitor.visit_borrowed_str(self.0)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        // empty cells are left out, so every cell that is deserialized has a value
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        visitor.visit_enum(self.0.into_deserializer())
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 char str string bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, ValueError> for Cell<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+fn parse_csv(source: &str) -> Result<Vec<Entry>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(source.as_bytes());
+    let headers = reader.headers().map_err(|e| e.to_string())?.clone();
+    Ok(reader
+        .records()
+        .map(|record| {
+            let record = match record {
+                Ok(record) => record,
+                Err(e) => {
+                    return Entry {
+                        line: e.position().map_or(0, csv::Position::line),
+                        badge: Err(e.to_string()),
+                    }
+                }
+            };
+            let cells = headers
+                .iter()
+                .zip(&record)
+                .filter(|(_, cell)| !cell.is_empty())
+                .map(|(header, cell)| (header, Cell(cell)));
+            Entry {
+                line: record.position().map_or(0, csv::Position::line),
+                badge: BadgeArgs::deserialize(MapDeserializer::new(cells))
+                    .map_err(|e| e.to_string()),
+            }
+        })
+        .collect())
+}
+
+/// Render a badge and write it, unless the file already has the same contents.
+fn write_entry(badge: &BadgeArgs, out_dir: &Path) -> Result<Status, String> {
+    let output = badge.output.as_ref().ok_or("missing `output`")?;
+    let path = out_dir.join(output);
+    let svg = badge.badge()?.render();
+    if fs::read(&path).is_ok_and(|existing| existing == svg.as_bytes()) {
+        return Ok(Status::Unchanged);
+    }
+    let write = || {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, svg.as_bytes())
+    };
+    write().map_err(|e| format!("failed to write {}: {e}", path.display()))?;
+    Ok(Status::Written)
+}
+
+/// Run the `batch` subcommand.
+///
+/// Entries that cannot be read or written are reported and skipped, so that one bad entry does
+/// not stop the rest of the badges being generated.
+pub fn run(args: &BatchArgs) -> ExitCode {
+    let manifest = args.manifest.display();
+    let Some(format) = args.format.or_else(|| Format::from_path(&args.manifest)) else {
+        eprintln!("error: cannot tell the format of {manifest}, pass `--format`");
+        return ExitCode::FAILURE;
+    };
+    let source = match fs::read_to_string(&args.manifest) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("error: failed to read {manifest}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let entries = match format {
+        Format::Toml => parse_toml(&source),
+        Format::Json => parse_json(&source),
+        Format::Csv => parse_csv(&source),
+    };
+    let entries = match entries {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("error: failed to parse {manifest}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let out_dir = args
+        .out_dir
+        .as_deref()
+        .unwrap_or_else(|| args.manifest.parent().unwrap_or_else(|| Path::new("")));
+    let (mut written, mut unchanged, mut failed) = (0, 0, 0);
+    for entry in entries {
+        match entry.badge.and_then(|badge| write_entry(&badge, out_dir)) {
+            Ok(Status::Written) => written += 1,
+            Ok(Status::Unchanged) => unchanged += 1,
+            Err(e) => {
+                eprintln!("error: {manifest}:{}: {e}", entry.line);
+                failed += 1;
+            }
+        }
+    }
+    eprintln!("{written} written, {unchanged} unchanged, {failed} failed");
+    if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,425 @@
+#![forbid(unsafe_code)]
+#![deny(
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::unwrap_in_result,
+    clippy::unwrap_used
+)]
+
+//! Generate badges from the command line.
+
+mod batch;
+
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    process::ExitCode,
+    str::FromStr,
+    time::Duration,
+};
+
+use badges::{
+    is_valid_id_prefix, Animation, Badge, Colour, LinkTarget, LogoPosition, Render, Segment,
+    Sparkline, Theme,
+};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::{de, Deserialize, Deserializer};
+
+/// Generate an SVG badge.
+#[derive(Debug, Parser)]
+#[command(version, about, args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    badge: BadgeArgs,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Generate every badge described in a TOML, JSON or CSV manifest.
+    Batch(batch::BatchArgs),
+}
+
+/// The options of a single badge, given on the command line or as an entry of a manifest.
+#[derive(Debug, Default, clap::Args, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+#[allow(clippy::struct_excessive_bools)]
+struct BadgeArgs {
+    /// The text on the left of the badge. Omit for a message-only badge.
+    #[arg(short, long, default_value = "")]
+    label: String,
+    /// The text on the right of the badge.
+    #[arg(short, long, default_value = "")]
+    message: String,
+    /// The background colour of the message, as a hex code like `#4c1` or `44cc11`, or a
+    /// shields.io colour name like `brightgreen`.
+    #[arg(short, long, value_parser = parse_colour)]
+    #[serde(deserialize_with = "deserialize_colour")]
+    colour: Option<Colour>,
+    /// The background colour of the label, as a hex code like `#555` or `555555`, or a
+    /// shields.io colour name like `grey`.
+    #[arg(long, value_parser = parse_colour)]
+    #[serde(deserialize_with = "deserialize_colour")]
+    label_colour: Option<Colour>,
+    /// The colour of the message when the viewer prefers a dark colour scheme.
+    #[arg(long, value_parser = parse_colour)]
+    #[serde(deserialize_with = "deserialize_colour")]
+    dark_colour: Option<Colour>,
+    /// The colour of the label when the viewer prefers a dark colour scheme.
+    #[arg(long, value_parser = parse_colour)]
+    #[serde(deserialize_with = "deserialize_colour")]
+    dark_label_colour: Option<Colour>,
+    /// The logo to show, as a URL or `data:` URI.
+    #[arg(long)]
+    logo: Option<String>,
+    /// The width of the logo.
+    #[arg(long)]
+    logo_width: Option<f32>,
+    /// Where the logo is placed. Defaults to `left`.
+    #[arg(long, value_enum)]
+    logo_position: Option<Position>,
+    /// The URL the label links to.
+    #[arg(long)]
+    label_link: Option<String>,
+    /// The URL the message links to.
+    #[arg(long)]
+    message_link: Option<String>,
+    /// Where the label and message links open. Defaults to `blank`.
+    #[arg(long, value_enum)]
+    link_target: Option<Target>,
+    /// The prefix of the ids of elements in the SVG: an ASCII letter or `_` followed by ASCII
+    /// letters, digits, `-` and `_`.
+    #[arg(long)]
+    id_prefix: Option<String>,
+    /// The maximum width of each segment. Longer text is cut short with an ellipsis.
+    #[arg(long)]
+    max_width: Option<f32>,
+    /// The maximum number of characters in each segment, including the ellipsis.
+    #[arg(long)]
+    max_chars: Option<usize>,
+    /// Split text into lines at newlines, and wrap it to the maximum width instead of cutting it
+    /// short.
+    #[arg(long)]
+    multi_line: bool,
+    /// The theme to start from. Defaults to `shields`.
+    #[arg(long, value_enum)]
+    theme: Option<Preset>,
+    /// Leave out the shadow behind the text.
+    #[arg(long)]
+    no_shadow: bool,
+    /// Leave out the gloss gradient over the badge.
+    #[arg(long)]
+    no_gradient: bool,
+    /// Draw the message as a progress bar, filled with its colour for this fraction of its
+    /// width, from 0 to 1.
+    #[arg(long, value_parser = parse_fraction)]
+    #[serde(deserialize_with = "deserialize_fraction")]
+    progress: Option<f32>,
+    /// The colour of the unfilled part of a progress bar.
+    #[arg(long, value_parser = parse_colour)]
+    #[serde(deserialize_with = "deserialize_colour")]
+    track_colour: Option<Colour>,
+    /// The colour of the unfilled part of a progress bar when the viewer prefers a dark colour
+    /// scheme.
+    #[arg(long, value_parser = parse_colour)]
+    #[serde(deserialize_with = "deserialize_colour")]
+    dark_track_colour: Option<Colour>,
+    /// Values to chart in a sparkline after the message, from oldest to newest, separated by
+    /// commas.
+    ///
+    /// In manifests, this is a list of numbers, or a single string of them separated by `|`.
+    #[arg(long, value_delimiter = ',', value_name = "VALUES")]
+    #[serde(deserialize_with = "deserialize_list")]
+    sparkline: Vec<f64>,
+    /// Fade the background of the message to this colour and back, repeatedly.
+    #[arg(long, value_parser = parse_colour, conflicts_with = "blink")]
+    #[serde(deserialize_with = "deserialize_colour")]
+    pulse: Option<Colour>,
+    /// Blink the text of the message on and off.
+    #[arg(long)]
+    blink: bool,
+    /// Another message to cycle through after the first. May be repeated.
+    ///
+    /// In manifests, this is a list, or a single string of messages separated by `|`.
+    #[arg(long = "cycle", value_name = "MESSAGE")]
+    #[serde(deserialize_with = "deserialize_list")]
+    cycle: Vec<String>,
+    /// The seconds a pulse or blink takes, or each cycled message is shown for. Defaults to 1.
+    #[arg(long)]
+    period: Option<f64>,
+    /// An extra segment after the message, as `TEXT` or `TEXT:#COLOUR`. May be repeated.
+    ///
+    /// In manifests, this is a list, or a single string of segments separated by `|`.
+    #[arg(long = "segment", value_name = "SEGMENT")]
+    #[serde(deserialize_with = "deserialize_list")]
+    segments: Vec<String>,
+    /// The file to write the badge to. Defaults to standard output.
+    ///
+    /// This is required in manifests, where it is relative to the output directory.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Position {
+    Left,
+    Message,
+    Right,
+}
+
+impl From<Position> for LogoPosition {
+    fn from(position: Position) -> Self {
+        match position {
+            Position::Left => Self::Left,
+            Position::Message => Self::Message,
+            Position::Right => Self::Right,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum Preset {
+    Shields,
+    FlatSquare,
+    Dark,
+}
+
+impl From<Preset> for Theme<'_> {
+    fn from(preset: Preset) -> Self {
+        match preset {
+            Preset::Shields => Self::shields(),
+            Preset::FlatSquare => Self::flat_square(),
+            Preset::Dark => Self::dark(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Target {
+    Blank,
+    Top,
+    Parent,
+    #[value(name = "self")]
+    #[serde(rename = "self")]
+    Current,
+}
+
+impl From<Target> for LinkTarget {
+    fn from(target: Target) -> Self {
+        match target {
+            Target::Blank => Self::Blank,
+            Target::Top => Self::Top,
+            Target::Parent => Self::Parent,
+            Target::Current => Self::Current,
+        }
+    }
+}
+
+/// Parse a shields.io colour name, or a hex colour with or without the leading `#`, as the
+/// server does.
+fn parse_colour(s: &str) -> Result<Colour, String> {
+    if let Some(colour) = Colour::from_shields_name(s) {
+        return Ok(colour);
+    }
+    let hex = if s.starts_with('#') {
+        s.to_owned()
+    } else {
+        format!("#{s}")
+    };
+    Colour::from_hex(&hex).ok_or_else(|| {
+        format!("expected a hex colour like `#4c1` or a colour name like `brightgreen`, got `{s}`")
+    })
+}
+
+/// Check that the fraction of a progress bar is a finite number.
+fn finite_fraction(fraction: f32) -> Result<f32, String> {
+    if fraction.is_finite() {
+        Ok(fraction)
+    } else {
+        Err(format!("expected a number from 0 to 1, got `{fraction}`"))
+    }
+}
+
+fn parse_fraction(s: &str) -> Result<f32, String> {
+    s.parse()
+        .map_err(|e| format!("invalid number `{s}`: {e}"))
+        .and_then(finite_fraction)
+}
+
+/// Parse an extra segment, which may end with `:#colour`.
+fn parse_segment(s: &str) -> Result<Segment<'_>, String> {
+    match s.rsplit_once(":#") {
+        Some((text, colour)) => Ok(Segment::new(text).colour(parse_colour(colour)?)),
+        None => Ok(Segment::new(s)),
+    }
+}
+
+fn deserialize_colour<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Colour>, D::Error> {
+    Option::<String>::deserialize(d)?
+        .map(|s| parse_colour(&s))
+        .transpose()
+        .map_err(de::Error::custom)
+}
+
+fn deserialize_fraction<'de, D: Deserializer<'de>>(d: D) -> Result<Option<f32>, D::Error> {
+    Option::<f32>::deserialize(d)?
+        .map(finite_fraction)
+        .transpose()
+        .map_err(de::Error::custom)
+}
+
+/// Deserialize a list in a manifest, which is either a list or a single string of items
+/// separated by `|`, the only way to write one in a CSV cell.
+fn deserialize_list<'de, D, T>(d: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + FromStr,
+    T::Err: fmt::Display,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum List<T> {
+        Joined(String),
+        List(Vec<T>),
+    }
+    match List::deserialize(d)? {
+        List::Joined(s) => s
+            .split('|')
+            .filter(|item| !item.is_empty())
+            .map(|item| {
+                item.parse()
+                    .map_err(|e| de::Error::custom(format!("invalid item `{item}`: {e}")))
+            })
+            .collect(),
+        List::List(list) => Ok(list),
+    }
+}
+
+impl BadgeArgs {
+    fn badge(&self) -> Result<Badge<'_>, String> {
+        let theme = Theme::from(self.theme.unwrap_or(Preset::Shields));
+        let mut builder = theme
+            .builder()
+            .label(&self.label)
+            .message(&self.message)
+            .logo_position(self.logo_position.unwrap_or(Position::Left).into())
+            .link_target(self.link_target.unwrap_or(Target::Blank).into());
+        if let Some(colour) = self.colour {
+            builder = builder.colour(colour);
+        }
+        if let Some(label_colour) = self.label_colour {
+            builder = builder.label_colour(label_colour);
+        }
+        if let Some(dark_colour) = self.dark_colour {
+            builder = builder.dark_colour(dark_colour);
+        }
+        if let Some(dark_label_colour) = self.dark_label_colour {
+            builder = builder.dark_label_colour(dark_label_colour);
+        }
+        if let Some(logo) = &self.logo {
+            builder = builder.logo(logo);
+        }
+        if let Some(logo_width) = self.logo_width {
+            builder = builder.logo_width(logo_width);
+        }
+        if let Some(label_link) = &self.label_link {
+            builder = builder.label_link(label_link);
+        }
+        if let Some(message_link) = &self.message_link {
+            builder = builder.message_link(message_link);
+        }
+        if let Some(id_prefix) = &self.id_prefix {
+            if !is_valid_id_prefix(id_prefix) {
+                return Err(format!("invalid id prefix `{id_prefix}`"));
+            }
+            builder = builder.id_prefix(id_prefix);
+        }
+        if let Some(max_width) = self.max_width {
+            builder = builder.max_width(max_width);
+        }
+        if let Some(max_chars) = self.max_chars {
+            builder = builder.max_chars(max_chars);
+        }
+        if self.multi_line {
+            builder = builder.multi_line();
+        }
+        if let Some(progress) = self.progress {
+            builder = builder.progress(progress);
+        }
+        if let Some(track_colour) = self.track_colour {
+            builder = builder.track_colour(track_colour);
+        }
+        if let Some(dark_track_colour) = self.dark_track_colour {
+            builder = builder.dark_track_colour(dark_track_colour);
+        }
+        if !self.sparkline.is_empty() {
+            builder = builder.sparkline(Sparkline::new(self.sparkline.iter().copied()));
+        }
+        if self.no_shadow {
+            builder = builder.shadow(false);
+        }
+        if self.no_gradient {
+            builder = builder.gradient(false);
+        }
+        let period = Duration::try_from_secs_f64(self.period.unwrap_or(1.0))
+            .map_err(|e| format!("invalid period: {e}"))?;
+        if let Some(colour) = self.pulse {
+            builder = builder.animation(Animation::Pulse { colour, period });
+        } else if self.blink {
+            builder = builder.animation(Animation::Blink { period });
+        }
+        if !self.cycle.is_empty() {
+            builder = builder.cycle(&self.cycle, period);
+        }
+        for segment in &self.segments {
+            let segment =
+                parse_segment(segment).map_err(|e| format!("invalid segment `{segment}`: {e}"))?;
+            builder = builder.segment(segment);
+        }
+        Ok(builder.build())
+    }
+}
+
+fn write_badge(badge: &Badge, output: Option<&PathBuf>) -> io::Result<()> {
+    if let Some(path) = output {
+        let mut file = BufWriter::new(File::create(path)?);
+        badge.render_to_io(&mut file)?;
+        file.flush()
+    } else {
+        let mut stdout = io::stdout().lock();
+        badge.render_to_io(&mut stdout)?;
+        writeln!(stdout)
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match cli.command {
+        Some(Command::Batch(args)) => batch::run(&args),
+        None => single(&cli.badge),
+    }
+}
+
+fn single(args: &BadgeArgs) -> ExitCode {
+    let badge = match args.badge() {
+        Ok(badge) => badge,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(e) = write_badge(&badge, args.output.as_ref()) {
+        match &args.output {
+            Some(path) => eprintln!("error: failed to write {}: {e}", path.display()),
+            None => eprintln!("error: failed to write badge: {e}"),
+        }
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
@@ -11,8 +11,39 @@ const fn scale8(x: u8, scale: u8) -> u8 {
     (((x as u16) * (1u16 + scale as u16)) >> 8) as u8
 }
 
+// the shields.io palette, used by the badges that mimic shields.io
+pub const BRIGHT_GREEN: Colour = Colour::from_colour_code(0x44_cc_11);
+pub const GREEN: Colour = Colour::from_colour_code(0x97_ca_00);
+pub const YELLOW_GREEN: Colour = Colour::from_colour_code(0xa4_a6_1d);
+pub const YELLOW: Colour = Colour::from_colour_code(0xdf_b3_17);
+pub const ORANGE: Colour = Colour::from_colour_code(0xfe_7d_37);
+pub const RED: Colour = Colour::from_colour_code(0xe0_5d_44);
+pub const BLUE: Colour = Colour::from_colour_code(0x00_7e_c6);
+pub const GREY: Colour = Colour::from_colour_code(0x55_55_55);
+pub const LIGHT_GREY: Colour = Colour::from_colour_code(0x9f_9f_9f);
+
+/// The colours shields.io refers to by name, and their aliases.
+const SHIELDS_COLOURS: &[(&str, Colour)] = &[
+    ("brightgreen", BRIGHT_GREEN),
+    ("green", GREEN),
+    ("yellowgreen", YELLOW_GREEN),
+    ("yellow", YELLOW),
+    ("orange", ORANGE),
+    ("red", RED),
+    ("blue", BLUE),
+    ("grey", GREY),
+    ("gray", GREY),
+    ("lightgrey", LIGHT_GREY),
+    ("lightgray", LIGHT_GREY),
+    ("success", BRIGHT_GREEN),
+    ("important", ORANGE),
+    ("critical", RED),
+    ("informational", BLUE),
+    ("inactive", LIGHT_GREY),
+];
+
 /// An RGB colour.
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub struct Colour {
     r: u8,
     g: u8,
@@ -206,8 +237,10 @@ impl Colour {
     #[must_use]
     #[cfg(feature = "hex_colours")]
     pub fn from_hex(hex: &str) -> Option<Self> {
-        let long_hex = Regex::new(r"^#([0-9a-fA-F]{2})([0-9a-fA-F]{2})([0-9a-fA-F]{2})$").unwrap();
-        let short_hex = Regex::new(r"^#([0-9a-fA-F])([0-9a-fA-F])([0-9a-fA-F])$").unwrap();
+        let long_hex = Regex::new(r"^#([0-9a-fA-F]{2})([0-9a-fA-F]{2})([0-9a-fA-F]{2})$")
+            .expect("Invalid long hex regex");
+        let short_hex = Regex::new(r"^#([0-9a-fA-F])([0-9a-fA-F])([0-9a-fA-F])$")
+            .expect("Invalid short hex regex");
         if let Some(caps) = short_hex.captures(hex) {
             let mut it = caps.iter();
             it.next()?;
@@ -241,6 +274,46 @@ impl Colour {
         None
     }
 
+    /// Look up one of the colours shields.io refers to by name.
+    ///
+    /// The names are `brightgreen`, `green`, `yellowgreen`, `yellow`, `orange`, `red`, `blue`,
+    /// `grey` and `lightgrey`, along with the aliases `success`, `important`, `critical`,
+    /// `informational` and `inactive`. These differ from the CSS colours of the same name, which
+    /// are available as constants such as [`Colour::GREEN`].
+    ///
+    /// Returns `None` if the name is not known. Names are matched case-insensitively.
+    #[must_use]
+    pub fn from_shields_name(name: &str) -> Option<Self> {
+        SHIELDS_COLOURS
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|&(_, colour)| colour)
+    }
+
+    /// Blend this colour with `other`.
+    ///
+    /// An `amount` of `0.0` gives this colour and `1.0` gives `other`, with values in between
+    /// mixing the two linearly. The amount is clamped to that range.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn mix(self, other: Self, amount: f32) -> Self {
+        let amount = if amount.is_nan() {
+            0.0
+        } else {
+            amount.clamp(0.0, 1.0)
+        };
+        let channel = |a: u8, b: u8| {
+            (f32::from(b) - f32::from(a))
+                .mul_add(amount, f32::from(a))
+                .round() as u8
+        };
+        Self {
+            r: channel(self.r, other.r),
+            g: channel(self.g, other.g),
+            b: channel(self.b, other.b),
+        }
+    }
+
     /// Create a new colour from RGB values.
     #[must_use]
     pub const fn from_rgb(r: u8, g: u8, b: u8) -> Self {
@@ -275,9 +348,9 @@ impl FromStr for Colour {
         Self::from_hex(s).map_or_else(
             || {
                 Err(format!(
-                    r#"{s} is not a valid CSS hex string.
+                    r"{s} is not a valid CSS hex string.
                     
-To use named colours, make use of the constants provided by the `Colour` struct. e.g. Colour::WHITE"#
+To use named colours, make use of the constants provided by the `Colour` struct. e.g. Colour::WHITE"
                 ))
             },
             Ok,
@@ -0,0 +1,239 @@
+//! Helpers for formatting numbers, sizes, durations and versions the way shields.io does.
+//!
+//! Using these for badge messages keeps badges from different sources consistent.
+//!
+//! # Example
+//!
+//! ```rust
+//! use badges::format;
+//! use std::time::Duration;
+//! assert_eq!(format::metric(1234.0), "1.2k");
+//! assert_eq!(format::bytes(12_900_000), "12.3 MiB");
+//! assert_eq!(format::duration(Duration::from_secs(151)), "2m 31s");
+//! assert_eq!(format::ordinal(22), "22nd");
+//! assert_eq!(format::version("1.2.3-rc1"), "v1.2.3-rc1");
+//! ```
+
+use std::time::Duration;
+
+use crate::colour::{Colour, BLUE, ORANGE};
+
+const METRIC_PREFIXES: [&str; 8] = ["k", "M", "G", "T", "P", "E", "Z", "Y"];
+const BINARY_PREFIXES: [&str; 8] = ["Ki", "Mi", "Gi", "Ti", "Pi", "Ei", "Zi", "Yi"];
+
+/// Format `value` with `decimals` decimal places, leaving off a fractional part of zero.
+fn trimmed(value: f64, decimals: usize) -> String {
+    let s = format!("{value:.decimals$}");
+    let s = if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &s
+    };
+    // small negative numbers round to zero, which has no sign
+    if s == "-0" { "0" } else { s }.to_owned()
+}
+
+/// Format a number with a metric suffix, e.g. `1.2k` or `34M`.
+///
+/// Numbers below 1000 are shown as they are. Above that, one decimal place is shown while the
+/// scaled number is below 10, unless it is zero. Infinities are shown as `∞` and `-∞`, and NaN
+/// as `NaN`.
+#[must_use]
+#[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+pub fn metric(value: f64) -> String {
+    let sign = if value < 0.0 { "-" } else { "" };
+    if value.is_nan() {
+        return "NaN".to_owned();
+    }
+    if value.is_infinite() {
+        return format!("{sign}∞");
+    }
+    let abs = value.abs();
+    for (i, prefix) in METRIC_PREFIXES.iter().enumerate().rev() {
+        let limit = 1000f64.powi(i as i32 + 1);
+        if abs < limit {
+            continue;
+        }
+        let scaled = abs / limit;
+        if scaled < 10.0 && format!("{scaled:.1}").ends_with(|c| c != '0') {
+            return format!("{sign}{scaled:.1}{prefix}");
+        }
+        let rounded = scaled.round();
+        return match METRIC_PREFIXES.get(i + 1) {
+            Some(next) if rounded >= 1000.0 => format!("{sign}1{next}"),
+            _ => format!("{sign}{rounded}{prefix}"),
+        };
+    }
+    trimmed(value, 2)
+}
+
+/// Format a number of bytes with a binary prefix, e.g. `512 B` or `12.3 MiB`.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn bytes(bytes: u64) -> String {
+    let mut value = bytes as f64;
+    if value < 1024.0 {
+        return format!("{bytes} B");
+    }
+    for prefix in BINARY_PREFIXES {
+        value /= 1024.0;
+        if value < 1024.0 || prefix == "Yi" {
+            return format!("{} {prefix}B", trimmed(value, 1));
+        }
+    }
+    unreachable!("the largest prefix always returns")
+}
+
+/// Format a duration as its two largest units, e.g. `2m 31s` or `3d 4h`.
+///
+/// Durations under a second are shown in milliseconds.
+#[must_use]
+pub fn duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs == 0 {
+        return format!("{}ms", duration.subsec_millis());
+    }
+    let units = [
+        (secs / 86_400, "d"),
+        (secs / 3600 % 24, "h"),
+        (secs / 60 % 60, "m"),
+        (secs % 60, "s"),
+    ];
+    let first = units.iter().position(|&(n, _)| n > 0).unwrap_or(3);
+    units[first..]
+        .iter()
+        .take(2)
+        .filter(|&&(n, _)| n > 0)
+        .map(|(n, unit)| format!("{n}{unit}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Format a number as an ordinal, e.g. `1st`, `22nd` or `113th`.
+#[must_use]
+pub fn ordinal(n: u64) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{n}{suffix}")
+}
+
+/// Format a percentage rounded to a whole number, e.g. `91%`.
+#[must_use]
+pub fn percent(percentage: f64) -> String {
+    let rounded = percentage.round();
+    // e.g. `-0.3` rounds to `-0.0`, which would otherwise be shown as `-0%`
+    format!("{}%", if rounded == 0.0 { 0.0 } else { rounded })
+}
+
+/// Format a version, adding a `v` prefix if it starts with a digit, e.g. `v1.2.3`.
+#[must_use]
+pub fn version(version: &str) -> String {
+    if version.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("v{version}")
+    } else {
+        version.to_owned()
+    }
+}
+
+/// Whether `version` is a pre-release, i.e. has a major version of zero or a pre-release tag
+/// such as `-rc1` or `beta`.
+#[must_use]
+pub fn is_prerelease(version: &str) -> bool {
+    let version = version.strip_prefix(['v', 'V']).unwrap_or(version);
+    let core = version.split(['+']).next().unwrap_or(version);
+    core.starts_with("0.")
+        || core == "0"
+        || core.contains('-')
+        || ["alpha", "beta", "rc", "dev", "pre", "snapshot"]
+            .iter()
+            .any(|tag| core.to_ascii_lowercase().contains(tag))
+}
+
+/// The colour shields.io gives a version: orange for pre-releases and blue otherwise.
+#[must_use]
+pub fn version_colour(version: &str) -> Colour {
+    if is_prerelease(version) {
+        ORANGE
+    } else {
+        BLUE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metric() {
+        let cases = [
+            (0.0, "0"),
+            (999.0, "999"),
+            (1.5, "1.5"),
+            (1000.0, "1k"),
+            (1049.0, "1k"),
+            (1234.0, "1.2k"),
+            (12_345.0, "12k"),
+            (999_499.0, "999k"),
+            (999_999.0, "1M"),
+            (3_400_000.0, "3.4M"),
+            (-1234.0, "-1.2k"),
+            (-0.001, "0"),
+            (f64::INFINITY, "∞"),
+            (f64::NEG_INFINITY, "-∞"),
+            (f64::NAN, "NaN"),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(metric(value), expected, "{value}");
+        }
+    }
+
+    #[test]
+    fn test_bytes() {
+        assert_eq!(bytes(0), "0 B");
+        assert_eq!(bytes(1023), "1023 B");
+        assert_eq!(bytes(1024), "1 KiB");
+        assert_eq!(bytes(1536), "1.5 KiB");
+        assert_eq!(bytes(12_900_000), "12.3 MiB");
+        assert_eq!(bytes(u64::MAX), "16 EiB");
+    }
+
+    #[test]
+    fn test_duration() {
+        assert_eq!(duration(Duration::from_millis(450)), "450ms");
+        assert_eq!(duration(Duration::from_secs(5)), "5s");
+        assert_eq!(duration(Duration::from_secs(151)), "2m 31s");
+        assert_eq!(duration(Duration::from_hours(1)), "1h");
+        assert_eq!(duration(Duration::from_secs(3601)), "1h");
+        assert_eq!(duration(Duration::from_secs(93_784)), "1d 2h");
+    }
+
+    #[test]
+    fn test_ordinal_and_percent() {
+        let ordinals = [1, 2, 3, 4, 11, 12, 13, 21, 102, 111].map(ordinal);
+        assert_eq!(
+            ordinals,
+            ["1st", "2nd", "3rd", "4th", "11th", "12th", "13th", "21st", "102nd", "111th"]
+        );
+        assert_eq!(percent(91.3), "91%");
+        assert_eq!(percent(99.5), "100%");
+        assert_eq!(percent(-0.3), "0%");
+        assert_eq!(percent(-0.5), "-1%");
+    }
+
+    #[test]
+    fn test_version() {
+        assert_eq!(version("1.2.3"), "v1.2.3");
+        assert_eq!(version("v1.2.3"), "v1.2.3");
+        assert_eq!(version("nightly"), "nightly");
+        assert_eq!(version_colour("1.2.3"), BLUE);
+        assert_eq!(version_colour("v2.0.0+build.5"), BLUE);
+        assert_eq!(version_colour("1.2.3-rc1"), ORANGE);
+        assert_eq!(version_colour("0.4.1"), ORANGE);
+        assert_eq!(version_colour("2.0.0beta"), ORANGE);
+    }
+}
@@ -0,0 +1,159 @@
+use std::hash::{Hash, Hasher};
+
+/// The size of the text the font metrics are measured at, in pixels.
+const BASE_FONT_SIZE: f32 = 11.0;
+
+/// The dimensions of a [`Badge`](crate::Badge): its height, padding, corner radius, font size
+/// and so on.
+///
+/// The defaults match shields.io. Text is measured at the configured font size, so larger badges
+/// are laid out for their size rather than scaled up, which keeps them sharp on high-DPI screens.
+///
+/// # Example
+///
+/// ```rust
+/// use badges::{Badge, Geometry};
+/// let badge = Badge::builder()
+///     .label("build")
+///     .message("passing")
+///     .geometry(Geometry::new().scaled(1.6))
+///     .build();
+/// assert!((badge.layout().height() - 32.0).abs() < 1e-3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Geometry {
+    pub(crate) height: f32,
+    pub(crate) horizontal_padding: f32,
+    pub(crate) corner_radius: f32,
+    pub(crate) font_size: f32,
+    pub(crate) line_height: f32,
+    pub(crate) logo_size: f32,
+    pub(crate) logo_padding: f32,
+    pub(crate) shadow_offset: (f32, f32),
+}
+
+impl Geometry {
+    /// The geometry of shields.io badges: 20 pixels tall with 11 pixel text.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            height: 20.0,
+            horizontal_padding: 5.0,
+            corner_radius: 3.0,
+            font_size: BASE_FONT_SIZE,
+            line_height: 14.0,
+            logo_size: 14.0,
+            logo_padding: 3.0,
+            shadow_offset: (0.0, 1.0),
+        }
+    }
+
+    /// Set the height of a single-line badge.
+    ///
+    /// Each extra line of a multi-line badge adds [`Geometry::line_height`] to this.
+    #[must_use]
+    pub const fn height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Set the space between the edges of each segment and its text or logo.
+    #[must_use]
+    pub const fn horizontal_padding(mut self, horizontal_padding: f32) -> Self {
+        self.horizontal_padding = horizontal_padding;
+        self
+    }
+
+    /// Set the radius of the badge's rounded corners. Use `0.0` for square corners.
+    #[must_use]
+    pub const fn corner_radius(mut self, corner_radius: f32) -> Self {
+        self.corner_radius = corner_radius;
+        self
+    }
+
+    /// Set the size of the text, in pixels.
+    #[must_use]
+    pub const fn font_size(mut self, font_size: f32) -> Self {
+        self.font_size = font_size;
+        self
+    }
+
+    /// Set the distance between the baselines of the lines of multi-line text.
+    #[must_use]
+    pub const fn line_height(mut self, line_height: f32) -> Self {
+        self.line_height = line_height;
+        self
+    }
+
+    /// Set the height of logos, and the width of logos that do not set their own.
+    #[must_use]
+    pub const fn logo_size(mut self, logo_size: f32) -> Self {
+        self.logo_size = logo_size;
+        self
+    }
+
+    /// Set the gap between a logo and the text next to it.
+    #[must_use]
+    pub const fn logo_padding(mut self, logo_padding: f32) -> Self {
+        self.logo_padding = logo_padding;
+        self
+    }
+
+    /// Set how far the shadow behind text is offset from it, to the right and down.
+    #[must_use]
+    pub const fn shadow_offset(mut self, x: f32, y: f32) -> Self {
+        self.shadow_offset = (x, y);
+        self
+    }
+
+    /// Scale every dimension by `factor`, such as `1.6` for 32 pixel tall badges.
+    #[must_use]
+    pub fn scaled(self, factor: f32) -> Self {
+        Self {
+            height: self.height * factor,
+            horizontal_padding: self.horizontal_padding * factor,
+            corner_radius: self.corner_radius * factor,
+            font_size: self.font_size * factor,
+            line_height: self.line_height * factor,
+            logo_size: self.logo_size * factor,
+            logo_padding: self.logo_padding * factor,
+            shadow_offset: (self.shadow_offset.0 * factor, self.shadow_offset.1 * factor),
+        }
+    }
+
+    /// The factor text measured by [`font::measure`](crate::font::measure) is scaled by.
+    pub(crate) fn text_scale(&self) -> f32 {
+        self.font_size / BASE_FONT_SIZE
+    }
+
+    /// The distance from the top of a line of text to its baseline, when it is centred in a
+    /// badge of the single-line height.
+    pub(crate) fn baseline(&self) -> f32 {
+        // 14 for the default 20 pixel height and 11 pixel text
+        0.5f32.mul_add(self.height, self.font_size * 4.0 / BASE_FONT_SIZE)
+    }
+}
+
+impl Default for Geometry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hash for Geometry {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for value in [
+            self.height,
+            self.horizontal_padding,
+            self.corner_radius,
+            self.font_size,
+            self.line_height,
+            self.logo_size,
+            self.logo_padding,
+            self.shadow_offset.0,
+            self.shadow_offset.1,
+        ] {
+            value.to_bits().hash(state);
+        }
+    }
+}
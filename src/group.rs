@@ -0,0 +1,385 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Write},
+    hash::{Hash, Hasher},
+};
+
+use crate::{
+    badge::{assert_valid_id_prefix, Badge, Ids},
+    hash::Fnv1a,
+    layout::Layout,
+    traits::{self, IteratorExt, Render},
+    xml::Writer,
+};
+
+/// How the badges of a [`BadgeGroup`] are arranged.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Arrangement {
+    /// All badges side by side in a single row. This is the default.
+    #[default]
+    Row,
+    /// All badges stacked in a single column.
+    Column,
+    /// A grid with the given number of columns, filled row by row.
+    ///
+    /// Every column is as wide as its widest badge, so badges line up vertically.
+    Grid {
+        /// The number of badges in each row. Zero is treated as one.
+        columns: usize,
+    },
+    /// Rows of badges that wrap onto a new row once they would exceed `max_width`.
+    ///
+    /// A badge wider than `max_width` is placed on a row of its own.
+    Wrap {
+        /// The maximum width of a row.
+        max_width: f32,
+    },
+}
+
+/// A set of [`Badge`]s rendered together into a single SVG.
+///
+/// Each badge is drawn as a nested `<svg>` at its position in the [`Arrangement`], with the ids
+/// of its elements namespaced so that badges in the same document do not clash.
+///
+/// # Example
+///
+/// ```rust
+/// use badges::{Arrangement, Badge, BadgeGroup, Render};
+/// let group = BadgeGroup::new(Arrangement::Grid { columns: 2 })
+///     .badge(Badge::builder().label("build").message("passing").build())
+///     .badge(Badge::builder().label("coverage").message("91%").build())
+///     .badge(Badge::builder().label("license").message("MIT").build())
+///     .gap(4.0);
+/// println!("{}", group.render());
+/// ```
+#[derive(Debug, Clone)]
+pub struct BadgeGroup<'a> {
+    badges: Vec<Badge<'a>>,
+    arrangement: Arrangement,
+    horizontal_gap: f32,
+    vertical_gap: f32,
+    id_prefix: Option<&'a str>,
+    title: Option<&'a str>,
+}
+
+impl Default for BadgeGroup<'_> {
+    fn default() -> Self {
+        Self::new(Arrangement::default())
+    }
+}
+
+impl<'a> BadgeGroup<'a> {
+    /// Create an empty group with the given arrangement.
+    ///
+    /// Badges are separated by a gap of 4 in both directions unless
+    /// [`BadgeGroup::horizontal_gap`] or [`BadgeGroup::vertical_gap`] are called.
+    #[must_use]
+    pub const fn new(arrangement: Arrangement) -> Self {
+        Self {
+            badges: Vec::new(),
+            arrangement,
+            horizontal_gap: 4.0,
+            vertical_gap: 4.0,
+            id_prefix: None,
+            title: None,
+        }
+    }
+
+    /// Add a badge to the end of the group.
+    #[must_use]
+    pub fn badge(mut self, badge: Badge<'a>) -> Self {
+        self.badges.push(badge);
+        self
+    }
+
+    /// Add several badges to the end of the group.
+    #[must_use]
+    pub fn badges(mut self, badges: impl IntoIterator<Item = Badge<'a>>) -> Self {
+        self.badges.extend(badges);
+        self
+    }
+
+    /// Set the arrangement of the badges.
+    #[must_use]
+    pub const fn arrangement(mut self, arrangement: Arrangement) -> Self {
+        self.arrangement = arrangement;
+        self
+    }
+
+    /// Set the space between badges in the same row.
+    #[must_use]
+    pub const fn horizontal_gap(mut self, gap: f32) -> Self {
+        self.horizontal_gap = gap;
+        self
+    }
+
+    /// Set the space between rows of badges.
+    #[must_use]
+    pub const fn vertical_gap(mut self, gap: f32) -> Self {
+        self.vertical_gap = gap;
+        self
+    }
+
+    /// Set the space between badges in both directions.
+    #[must_use]
+    pub const fn gap(self, gap: f32) -> Self {
+        self.horizontal_gap(gap).vertical_gap(gap)
+    }
+
+    /// Set the prefix of the ids of elements in the rendered SVG.
+    ///
+    /// The ids of each badge are namespaced under this prefix, replacing any prefix set with
+    /// [`BadgeBuilder::id_prefix`](crate::BadgeBuilder::id_prefix). If not set, a prefix is
+    /// derived from the contents of the group.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the prefix is not valid, in the same way as
+    /// [`BadgeBuilder::id_prefix`](crate::BadgeBuilder::id_prefix).
+    #[must_use]
+    pub fn id_prefix(mut self, id_prefix: &'a str) -> Self {
+        assert_valid_id_prefix(id_prefix);
+        self.id_prefix = Some(id_prefix);
+        self
+    }
+
+    /// Set the title of the combined SVG.
+    ///
+    /// This is shown as a tooltip and read out by screen readers. If not set, the accessible
+    /// texts of the badges are joined with `"; "`.
+    #[must_use]
+    pub const fn title(mut self, title: &'a str) -> Self {
+        self.title = Some(title);
+        self
+    }
+
+    fn id_prefix_or_default(&self) -> Cow<'a, str> {
+        self.id_prefix.map_or_else(
+            || {
+                let mut hasher = Fnv1a::default();
+                self.badges.hash(&mut hasher);
+                Cow::Owned(format!("g{:016x}", hasher.finish()))
+            },
+            Cow::Borrowed,
+        )
+    }
+
+    /// Position every badge, returning the offsets of each badge and the total size.
+    fn positions(&self, layouts: &[Layout]) -> (Vec<(f32, f32)>, f32, f32) {
+        let rows: Vec<&[Layout]> = match self.arrangement {
+            Arrangement::Row => layouts.chunks(layouts.len().max(1)).collect(),
+            Arrangement::Column => layouts.chunks(1).collect(),
+            Arrangement::Grid { columns } => layouts.chunks(columns.max(1)).collect(),
+            Arrangement::Wrap { max_width } => self.wrap(layouts, max_width),
+        };
+        // in a grid, every badge in a column starts at the same x
+        let mut column_widths = Vec::new();
+        if matches!(self.arrangement, Arrangement::Grid { .. }) {
+            for row in &rows {
+                for (i, layout) in row.iter().enumerate() {
+                    if i == column_widths.len() {
+                        column_widths.push(0.0f32);
+                    }
+                    column_widths[i] = column_widths[i].max(layout.width());
+                }
+            }
+        }
+        let mut positions = Vec::with_capacity(layouts.len());
+        let (mut width, mut y) = (0.0f32, 0.0f32);
+        for (row_index, row) in rows.iter().enumerate() {
+            if row_index > 0 {
+                y += self.vertical_gap;
+            }
+            let mut x = 0.0f32;
+            let mut row_height = 0.0f32;
+            for (i, layout) in row.iter().enumerate() {
+                if i > 0 {
+                    x += self.horizontal_gap;
+                }
+                positions.push((x, y));
+                x += column_widths
+                    .get(i)
+                    .copied()
+                    .unwrap_or_else(|| layout.width());
+                row_height = row_height.max(layout.height());
+            }
+            width = width.max(x);
+            y += row_height;
+        }
+        (positions, width, y)
+    }
+
+    /// Break the badges into rows no wider than `max_width`.
+    fn wrap<'l>(&self, layouts: &'l [Layout], max_width: f32) -> Vec<&'l [Layout]> {
+        let mut rows = Vec::new();
+        let (mut start, mut x) = (0, 0.0f32);
+        for (i, layout) in layouts.iter().enumerate() {
+            let end = if i == start {
+                layout.width()
+            } else {
+                x + self.horizontal_gap + layout.width()
+            };
+            if i > start && end > max_width {
+                rows.push(&layouts[start..i]);
+                start = i;
+                x = layout.width();
+            } else {
+                x = end;
+            }
+        }
+        if start < layouts.len() {
+            rows.push(&layouts[start..]);
+        }
+        rows
+    }
+}
+
+impl<'a> Render<'a> for BadgeGroup<'a> {
+    fn render(&self) -> Cow<'a, str> {
+        traits::render_string(self)
+    }
+
+    fn render_to(&self, w: &mut dyn Write) -> fmt::Result {
+        let layouts: Vec<Layout> = self.badges.iter().map(Badge::layout).collect();
+        let (positions, width, height) = self.positions(&layouts);
+        let prefix = self.id_prefix_or_default();
+        let ids: Vec<Ids> = (0..self.badges.len())
+            .map(|i| Ids::new(&format!("{prefix}-{i}")))
+            .collect();
+        let a11y_texts: Vec<String> = self.badges.iter().map(Badge::accessible_text).collect();
+        let a11y_text = self
+            .title
+            .map_or_else(|| Cow::Owned(a11y_texts.iter().join("; ")), Cow::Borrowed);
+        Writer::new(w)
+            .element("svg")
+            .attr("xmlns", "http://www.w3.org/2000/svg")
+            .attr("xmlns:xlink", "http://www.w3.org/1999/xlink")
+            .attr_float("width", width)
+            .attr_float("height", height)
+            .attr("role", "img")
+            .attr("aria-label", a11y_text.as_ref())
+            .content(|w| {
+                w.element("title").text(&a11y_text)?;
+                for (i, badge) in self.badges.iter().enumerate() {
+                    badge.write_svg(w, &layouts[i], &ids[i], &a11y_texts[i], positions[i])?;
+                }
+                Ok(())
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn badges() -> Vec<Badge<'static>> {
+        vec![
+            Badge::builder().label("build").message("passing").build(),
+            Badge::builder().label("coverage").message("91%").build(),
+            Badge::builder().message("MIT").build(),
+        ]
+    }
+
+    fn widths() -> Vec<f32> {
+        badges().iter().map(|b| b.layout().width()).collect()
+    }
+
+    fn size(group: &BadgeGroup) -> (f32, f32) {
+        let layouts: Vec<Layout> = group.badges.iter().map(Badge::layout).collect();
+        let (_, width, height) = group.positions(&layouts);
+        (width, height)
+    }
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-3
+    }
+
+    #[test]
+    fn test_row_and_column() {
+        let w = widths();
+        let row = BadgeGroup::new(Arrangement::Row).badges(badges());
+        let (width, height) = size(&row);
+        assert!(close(width, w[0] + w[1] + w[2] + 8.0));
+        assert!(close(height, 20.0));
+
+        let column = BadgeGroup::new(Arrangement::Column)
+            .badges(badges())
+            .vertical_gap(2.0);
+        let (width, height) = size(&column);
+        assert!(close(width, w[0].max(w[1]).max(w[2])));
+        assert!(close(height, 64.0));
+    }
+
+    #[test]
+    fn test_grid_aligns_columns() {
+        let w = widths();
+        let group = BadgeGroup::new(Arrangement::Grid { columns: 2 })
+            .badges(badges())
+            .gap(0.0);
+        let layouts: Vec<Layout> = group.badges.iter().map(Badge::layout).collect();
+        let (positions, width, height) = group.positions(&layouts);
+        assert!(close(width, w[0].max(w[2]) + w[1]));
+        assert!(close(height, 40.0));
+        assert!(close(positions[1].0, w[0].max(w[2])));
+        assert_eq!(positions[2], (0.0, 20.0));
+    }
+
+    #[test]
+    fn test_wrap() {
+        let w = widths();
+        let group = BadgeGroup::new(Arrangement::Wrap {
+            max_width: w[0] + w[1] + 4.0,
+        })
+        .badges(badges());
+        let (width, height) = size(&group);
+        assert!(close(width, w[0] + w[1] + 4.0));
+        assert!(close(height, 44.0));
+
+        // badges wider than the limit still get a row each
+        let narrow = BadgeGroup::new(Arrangement::Wrap { max_width: 1.0 }).badges(badges());
+        assert!(close(size(&narrow).1, 68.0));
+    }
+
+    #[test]
+    fn test_render() {
+        let svg = BadgeGroup::new(Arrangement::Column)
+            .badges(badges())
+            .id_prefix("readme")
+            .render();
+        assert!(svg.starts_with("<svg xmlns="));
+        assert!(svg.contains(r#"<linearGradient id="readme-0-s""#));
+        assert!(svg.contains(r#"clip-path="url(#readme-2-r)""#));
+        assert!(svg.contains(r#"y="24.0""#));
+        assert!(svg.contains("<title>build: passing; coverage: 91%; MIT</title>"));
+
+        let titled = BadgeGroup::default()
+            .badges(badges())
+            .title("Project status")
+            .render();
+        assert!(titled.contains(r#"aria-label="Project status""#));
+    }
+
+    #[test]
+    fn test_default_id_prefix_is_unique() {
+        let a = BadgeGroup::default().badges(badges());
+        let b = BadgeGroup::default().badges(badges().into_iter().rev());
+        assert_ne!(a.id_prefix_or_default(), b.id_prefix_or_default());
+        assert_eq!(
+            a.id_prefix_or_default(),
+            BadgeGroup::default()
+                .badges(badges())
+                .id_prefix_or_default()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid id prefix `x{}`")]
+    fn test_invalid_id_prefix() {
+        let _ = BadgeGroup::default().id_prefix("x{}");
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(size(&BadgeGroup::default()), (0.0, 0.0));
+    }
+}
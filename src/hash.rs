@@ -0,0 +1,34 @@
+use std::hash::Hasher;
+
+/// A 64-bit [FNV-1a](http://www.isthe.com/chongo/tech/comp/fnv/) hasher.
+///
+/// Unlike [`std::collections::hash_map::DefaultHasher`], the output of this hasher is not
+/// randomly seeded, so the same badge renders with the same ids every time. The bytes written
+/// by the std [`Hash`](std::hash::Hash) impls are not guaranteed to stay the same across Rust
+/// versions though, so neither are the ids derived from them.
+#[derive(Debug, Clone, Copy)]
+pub struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        // hash lengths the same way on 32 and 64-bit platforms
+        self.write_u64(i as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
@@ -0,0 +1,636 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    font::measure,
+    geometry::Geometry,
+    segment::{LogoSide, Segment},
+};
+
+fn round_up_to_odd(val: f32) -> f32 {
+    if val.rem_euclid(2.0) == 0.0 {
+        val + 1.0
+    } else {
+        val
+    }
+}
+
+fn preferred_width(text: &str) -> f32 {
+    if text.is_empty() {
+        return 0.0;
+    }
+    round_up_to_odd(measure(text))
+}
+
+/// The width of the text of a segment, which is that of its widest line.
+fn text_width(segment: &Segment) -> f32 {
+    segment.lines().map(preferred_width).fold(0.0, f32::max)
+}
+
+/// The widest the text of `segment` can be without going over its maximum width, before it is
+/// scaled to the font size.
+fn max_text_width(segment: &Segment, geometry: &Geometry) -> f32 {
+    segment.max_width.map_or(f32::INFINITY, |max_width| {
+        let logo_space = if segment.has_logo() {
+            segment.logo_width_or(geometry.logo_size) + geometry.logo_padding
+        } else {
+            0.0
+        };
+        let chart_width = segment.chart_width(geometry);
+        let chart_space = if chart_width > 0.0 {
+            chart_width + geometry.logo_padding
+        } else {
+            0.0
+        };
+        (2.0f32.mul_add(-geometry.horizontal_padding, max_width) - logo_space - chart_space)
+            / geometry.text_scale()
+    })
+}
+
+/// Shorten the text of `segment` to its maximum width and number of characters, or `None` if it
+/// already fits.
+///
+/// The text is cut on a grapheme boundary and ends with an ellipsis, which counts towards both
+/// limits. The maximum width is that of the whole segment, including its padding and logo.
+/// Multi-line segments are [wrapped](wrap) to their maximum width instead, so only the number of
+/// characters is checked for them.
+pub fn truncate(segment: &Segment, geometry: &Geometry) -> Option<String> {
+    const ELLIPSIS: &str = "\u{2026}";
+    let text = &segment.text;
+    let max_chars = segment.max_chars.unwrap_or(usize::MAX);
+    let max_text_width = if segment.multi_line {
+        f32::INFINITY
+    } else {
+        max_text_width(segment, geometry)
+    };
+    if text.graphemes(true).count() <= max_chars && preferred_width(text) <= max_text_width {
+        return None;
+    }
+    let ellipsis_width = measure(ELLIPSIS);
+    let mut width = 0.0;
+    let mut end = 0;
+    for (count, (index, grapheme)) in text.grapheme_indices(true).enumerate() {
+        width += measure(grapheme);
+        if count + 2 > max_chars || round_up_to_odd(width + ellipsis_width) > max_text_width {
+            break;
+        }
+        end = index + grapheme.len();
+    }
+    Some(format!("{}{ELLIPSIS}", text[..end].trim_end()))
+}
+
+/// Wrap the text of a multi-line `segment` onto as many lines as it takes to fit its maximum
+/// width, or `None` if it already fits.
+///
+/// Lines are broken at spaces where possible, and words too wide for a line of their own are
+/// broken between graphemes.
+pub fn wrap(segment: &Segment, geometry: &Geometry) -> Option<String> {
+    let max_text_width = max_text_width(segment, geometry);
+    if !segment.multi_line || text_width(segment) <= max_text_width {
+        return None;
+    }
+    let fits = |line: &str| preferred_width(line) <= max_text_width;
+    let mut wrapped = String::with_capacity(segment.text.len() + 8);
+    for line in segment.text.split('\n') {
+        if !wrapped.is_empty() {
+            wrapped.push('\n');
+        }
+        let mut current = String::new();
+        for word in line.split(' ').filter(|word| !word.is_empty()) {
+            if !current.is_empty() {
+                let joined = format!("{current} {word}");
+                if fits(&joined) {
+                    current = joined;
+                    continue;
+                }
+                wrapped.push_str(&current);
+                wrapped.push('\n');
+            }
+            // the word starts a new line, which it may still be too wide for
+            let mut rest = word;
+            while !fits(rest) {
+                let first = rest.graphemes(true).next().map_or(0, str::len);
+                let end = rest
+                    .grapheme_indices(true)
+                    .map(|(index, _)| index)
+                    .skip(1)
+                    .chain([rest.len()])
+                    .take_while(|&index| fits(&rest[..index]))
+                    .last()
+                    .unwrap_or(first);
+                if end == rest.len() {
+                    break;
+                }
+                wrapped.push_str(&rest[..end]);
+                wrapped.push('\n');
+                rest = &rest[end..];
+            }
+            rest.clone_into(&mut current);
+        }
+        wrapped.push_str(&current);
+    }
+    Some(wrapped)
+}
+
+/// The computed size of a [`Badge`](crate::Badge) and the positions of its segments.
+///
+/// All values are in SVG user units, which are pixels unless the badge is scaled. This is the
+/// same layout that rendering uses, so it can be used to position badges without parsing the
+/// rendered SVG.
+///
+/// # Example
+///
+/// ```rust
+/// use badges::Badge;
+/// let layout = Badge::builder().label("build").message("passing").build().layout();
+/// assert_eq!(layout.segments().len(), 2);
+/// assert!(layout.width() > layout.height());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layout {
+    width: f32,
+    height: f32,
+    segments: Vec<SegmentLayout>,
+}
+
+/// The computed position and size of a single [`Segment`] of a badge.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SegmentLayout {
+    rect: Rect,
+    text: Option<TextBox>,
+    shadow: Option<TextBox>,
+    logo: Option<Rect>,
+    chart: Option<Rect>,
+    lines: usize,
+}
+
+/// An axis-aligned rectangle.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rect {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+}
+
+/// The position of a line of text.
+///
+/// Text is centred horizontally on [`TextBox::x`], with its baseline at [`TextBox::y`], and is
+/// stretched or squashed to exactly [`TextBox::text_length`] so that it renders the same width
+/// regardless of the font used.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TextBox {
+    x: f32,
+    y: f32,
+    text_length: f32,
+}
+
+impl Layout {
+    pub(crate) fn new(segments: &[Segment], geometry: &Geometry, shadow: bool) -> Self {
+        let lines = segments
+            .iter()
+            .flat_map(Segment::frames)
+            .map(Segment::line_count)
+            .max()
+            .unwrap_or(1);
+        #[allow(clippy::cast_precision_loss)]
+        let height = geometry
+            .line_height
+            .mul_add(lines.saturating_sub(1) as f32, geometry.height);
+        let mut x = 0.0;
+        let segments = segments
+            .iter()
+            .enumerate()
+            .map(|(index, segment)| {
+                let mut layout = SegmentLayout::new(
+                    segment,
+                    geometry,
+                    x,
+                    height,
+                    nudge(segments, index),
+                    segments.len() == 1,
+                );
+                if !shadow {
+                    layout.shadow = None;
+                }
+                x += layout.rect.width;
+                layout
+            })
+            .collect();
+        Self {
+            width: x,
+            height,
+            segments,
+        }
+    }
+
+    /// The rectangle covered by the whole badge.
+    #[must_use]
+    pub const fn rect(&self) -> Rect {
+        Rect {
+            x: 0.0,
+            y: 0.0,
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// The total width of the badge.
+    #[must_use]
+    pub const fn width(&self) -> f32 {
+        self.width
+    }
+
+    /// The total height of the badge.
+    #[must_use]
+    pub const fn height(&self) -> f32 {
+        self.height
+    }
+
+    /// The layouts of the badge's segments, from left to right.
+    #[must_use]
+    pub fn segments(&self) -> &[SegmentLayout] {
+        &self.segments
+    }
+}
+
+impl SegmentLayout {
+    fn new(
+        segment: &Segment,
+        geometry: &Geometry,
+        x: f32,
+        height: f32,
+        nudge: f32,
+        sole: bool,
+    ) -> Self {
+        let scale = geometry.text_scale();
+        // cycling segments are sized for the widest and tallest of their texts
+        let text_width = segment.frames().map(text_width).fold(0.0, f32::max) * scale;
+        let logo_width = segment.logo_width_or(geometry.logo_size);
+        let padding = geometry.horizontal_padding;
+        // the sparkline goes after the text, with the same gap as a logo
+        let chart_width = segment.chart_width(geometry);
+        // the horizontal space taken up by the logo, including the gap next to the text or chart
+        let logo_space = if !segment.has_logo() {
+            0.0
+        } else if sole {
+            // shields.io leaves an extra pixel after the logo of a badge without a label, even
+            // when there is no text after it
+            logo_width + geometry.logo_padding + scale
+        } else if text_width == 0.0 && chart_width == 0.0 {
+            logo_width
+        } else {
+            logo_width + geometry.logo_padding
+        };
+        let chart_space = if chart_width == 0.0 || text_width == 0.0 {
+            chart_width
+        } else {
+            chart_width + geometry.logo_padding
+        };
+        let width = 2.0f32.mul_add(padding, text_width) + logo_space + chart_space;
+        let mut content_offset = padding;
+        let mut logo_offset = padding;
+        match segment.logo_side {
+            LogoSide::Start => content_offset += logo_space,
+            LogoSide::End => logo_offset = width - padding - logo_width,
+        }
+        let text_offset = nudge.mul_add(scale, content_offset);
+        let lines = segment.frames().map(Segment::line_count).max().unwrap_or(1);
+        // centre the lines vertically in badges made taller by other segments
+        #[allow(clippy::cast_precision_loss)]
+        let spare_height = geometry
+            .line_height
+            .mul_add(-((lines - 1) as f32), height - geometry.height);
+        let text = (text_width > 0.0).then(|| TextBox {
+            x: 0.5f32.mul_add(text_width, x + text_offset),
+            y: 0.5f32.mul_add(spare_height, geometry.baseline()),
+            text_length: segment.lines().next().map_or(0.0, preferred_width) * scale,
+        });
+        let (shadow_x, shadow_y) = geometry.shadow_offset;
+        Self {
+            rect: Rect {
+                x,
+                y: 0.0,
+                width,
+                height,
+            },
+            text,
+            shadow: text.map(|text| TextBox {
+                x: text.x + shadow_x,
+                y: text.y + shadow_y,
+                ..text
+            }),
+            logo: segment.has_logo().then_some(Rect {
+                x: x + logo_offset,
+                y: 0.5 * (height - geometry.logo_size),
+                width: logo_width,
+                height: geometry.logo_size,
+            }),
+            chart: (chart_width > 0.0).then_some(Rect {
+                x: x + content_offset + text_width + chart_space - chart_width,
+                y: 0.5 * (height - geometry.logo_size),
+                width: chart_width,
+                height: geometry.logo_size,
+            }),
+            lines,
+        }
+    }
+
+    /// The rectangle covered by the segment's background.
+    #[must_use]
+    pub const fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// The position of the segment's text, or `None` if it has no text.
+    ///
+    /// For [multi-line](crate::BadgeBuilder::multi_line) segments, this is the first line. Each
+    /// following line is centred on the same [`TextBox::x`], a
+    /// [line height](crate::Geometry::line_height) further down. For
+    /// [cycling](crate::Segment::cycle) segments, this is the segment's own text, and the others
+    /// are centred on the same point.
+    #[must_use]
+    pub const fn text(&self) -> Option<TextBox> {
+        self.text
+    }
+
+    /// The position of the shadow drawn behind the segment's text, or `None` if it has no text.
+    #[must_use]
+    pub const fn shadow(&self) -> Option<TextBox> {
+        self.shadow
+    }
+
+    /// The number of lines of text in the segment.
+    #[must_use]
+    pub const fn line_count(&self) -> usize {
+        self.lines
+    }
+
+    /// The rectangle covered by the segment's logo, or `None` if it has no logo.
+    #[must_use]
+    pub const fn logo(&self) -> Option<Rect> {
+        self.logo
+    }
+
+    /// The rectangle covered by the segment's [sparkline](crate::Sparkline), or `None` if it has
+    /// none.
+    #[must_use]
+    pub const fn chart(&self) -> Option<Rect> {
+        self.chart
+    }
+}
+
+impl Rect {
+    /// The horizontal position of the left edge of the rectangle.
+    #[must_use]
+    pub const fn x(&self) -> f32 {
+        self.x
+    }
+
+    /// The vertical position of the top edge of the rectangle.
+    #[must_use]
+    pub const fn y(&self) -> f32 {
+        self.y
+    }
+
+    /// The width of the rectangle.
+    #[must_use]
+    pub const fn width(&self) -> f32 {
+        self.width
+    }
+
+    /// The height of the rectangle.
+    #[must_use]
+    pub const fn height(&self) -> f32 {
+        self.height
+    }
+}
+
+impl TextBox {
+    /// The position of the line `index` lines below this one, which reads `text`.
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn line(self, index: usize, text: &str, geometry: &Geometry) -> Self {
+        Self {
+            y: geometry.line_height.mul_add(index as f32, self.y),
+            text_length: preferred_width(text) * geometry.text_scale(),
+            ..self
+        }
+    }
+
+    /// The horizontal position of the centre of the text.
+    #[must_use]
+    pub const fn x(&self) -> f32 {
+        self.x
+    }
+
+    /// The vertical position of the baseline of the text.
+    #[must_use]
+    pub const fn y(&self) -> f32 {
+        self.y
+    }
+
+    /// The width the text is rendered at.
+    #[must_use]
+    pub const fn text_length(&self) -> f32 {
+        self.text_length
+    }
+}
+
+/// Horizontal offset applied to the text of a segment so it matches the shields.io layout.
+///
+/// shields.io shifts the text of the outermost segments a pixel towards the seams between them,
+/// which we replicate so that badges look the same.
+#[allow(clippy::cast_precision_loss)]
+fn nudge(segments: &[Segment], index: usize) -> f32 {
+    match segments.len() {
+        0 | 1 => 0.0,
+        _ if index == 0 => 1.0,
+        n if index == n - 1 => -(segments[index].text.len().min(1) as f32),
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Badge, Geometry, Render, Sparkline};
+
+    #[test]
+    fn test_layout_matches_render() {
+        let badge = Badge::builder()
+            .label("build")
+            .message("passing")
+            .logo("x")
+            .build();
+        let layout = badge.layout();
+        let svg = badge.render();
+        assert!(svg.contains(&format!(r#"width="{:?}""#, layout.width())));
+        let [label, message] = layout.segments() else {
+            panic!("expected two segments");
+        };
+        assert!(label.rect().x().abs() < f32::EPSILON);
+        assert!((message.rect().x() - label.rect().width()).abs() < f32::EPSILON);
+        assert!(
+            (label.rect().width() + message.rect().width() - layout.width()).abs() < f32::EPSILON
+        );
+        for segment in layout.segments() {
+            let text = segment.text().expect("segment has no text");
+            assert!(svg.contains(&format!(
+                r#"x="{:?}" y="{:?}""#,
+                text.x() * 10.0,
+                text.y() * 10.0
+            )));
+            assert!(svg.contains(&format!(r#"textLength="{:?}""#, text.text_length() * 10.0)));
+            let shadow = segment.shadow().expect("segment has no shadow");
+            assert!((shadow.y() - text.y() - 1.0).abs() < f32::EPSILON);
+        }
+        let logo = label.logo().expect("label has no logo");
+        assert!(svg.contains(&format!(
+            r#"<image x="{:?}" y="{:?}" width="{:?}" height="{:?}""#,
+            logo.x(),
+            logo.y(),
+            logo.width(),
+            logo.height()
+        )));
+        assert!(message.logo().is_none());
+    }
+
+    #[test]
+    fn test_truncate() {
+        let badge = Badge::builder()
+            .label("branch")
+            .message("feature/a-very-long-branch-name")
+            .max_chars(10)
+            .build();
+        assert!(badge.render().contains(">feature/a\u{2026}</text>"));
+        assert!(badge
+            .render()
+            .contains("<title>branch: feature/a-very-long-branch-name</title>"));
+
+        let badge = Badge::builder()
+            .message("feature/a-very-long-branch-name")
+            .max_width(60.0)
+            .build();
+        let width = badge.layout().width();
+        assert!(width <= 60.0 && width > 50.0, "{width}");
+
+        // combining characters stay with their base character
+        let badge = Badge::builder()
+            .message("e\u{301}e\u{301}e\u{301}")
+            .max_chars(2)
+            .build();
+        assert!(badge.render().contains(">e\u{301}\u{2026}</text>"));
+
+        let badge = Badge::builder().message("short").max_chars(5).build();
+        assert!(badge.render().contains(">short</text>"));
+    }
+
+    #[test]
+    fn test_multi_line() {
+        let badge = Badge::builder()
+            .label("release")
+            .message("v1.4.0\n2026-10-01")
+            .multi_line()
+            .build();
+        let layout = badge.layout();
+        assert!((layout.height() - 34.0).abs() < f32::EPSILON);
+        let [label, message] = layout.segments() else {
+            panic!("expected two segments");
+        };
+        assert_eq!(label.line_count(), 1);
+        assert_eq!(message.line_count(), 2);
+        // the label is centred vertically, and the message starts a line higher
+        let label_text = label.text().expect("label has no text");
+        let message_text = message.text().expect("message has no text");
+        assert!((label_text.y() - message_text.y() - 7.0).abs() < f32::EPSILON);
+        let svg = badge.render();
+        assert_eq!(svg.matches("<tspan").count(), 4);
+        assert!(svg.contains(">2026-10-01</tspan>"));
+        assert!(svg.contains("<title>release: v1.4.0 2026-10-01</title>"));
+
+        // without opting in, newlines do not start new lines
+        let badge = Badge::builder().message("a\nb").build();
+        assert!((badge.layout().height() - 20.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_wrap() {
+        let badge = Badge::builder()
+            .message("the quick brown fox jumps over the lazy dog")
+            .max_width(80.0)
+            .multi_line()
+            .build();
+        let layout = badge.layout();
+        assert!(layout.width() <= 80.0);
+        assert!(layout.segments()[0].line_count() > 2);
+        let svg = badge.render();
+        assert!(svg.contains(">the quick</tspan>"));
+        assert!(!svg.contains('\u{2026}'));
+
+        // words too wide for a line of their own are broken up
+        let badge = Badge::builder()
+            .message("abcdefghijklmnopqrstuvwxyz")
+            .max_width(40.0)
+            .multi_line()
+            .build();
+        assert!(badge.layout().width() <= 40.0);
+        assert!(badge
+            .render()
+            .contains("<title>abcdefghijklmnopqrstuvwxyz</title>"));
+    }
+
+    #[test]
+    fn test_geometry() {
+        let builder = Badge::builder().label("build").message("passing").logo("x");
+        let default = builder.clone().build().layout();
+        let badge = builder.geometry(Geometry::new().scaled(2.0)).build();
+        let layout = badge.layout();
+        assert!((layout.height() - 40.0).abs() < f32::EPSILON);
+        let doubled = |scaled: f32, default: f32| (scaled / default - 2.0).abs() < 1e-4;
+        assert!(doubled(layout.width(), default.width()));
+        for (scaled, default) in layout.segments().iter().zip(default.segments()) {
+            let text = scaled.text().expect("segment has no text");
+            let default_text = default.text().expect("segment has no text");
+            assert!(doubled(text.x(), default_text.x()));
+            assert!(doubled(text.y(), default_text.y()));
+            assert!(doubled(text.text_length(), default_text.text_length()));
+        }
+        let logo = layout.segments()[0].logo().expect("label has no logo");
+        assert!((logo.height() - 28.0).abs() < f32::EPSILON);
+        let svg = badge.render();
+        assert!(svg.contains(r#"font-size="220""#));
+        assert!(svg.contains(r#"rx="6.0""#));
+    }
+
+    #[test]
+    fn test_sparkline() {
+        let builder = Badge::builder().label("bench").message("12ms");
+        let plain = builder.clone().build().layout().segments()[1];
+        let badge = builder
+            .sparkline(Sparkline::new([3.0, 1.0, 2.0]).width(30.0))
+            .build();
+        let message = badge.layout().segments()[1];
+        let chart = message.chart().expect("message has no chart");
+        // the chart is after the text, with a gap of the logo padding
+        assert!((message.rect().width() - plain.rect().width() - 33.0).abs() < 1e-3);
+        assert!(
+            (chart.x() + chart.width() + 5.0 - message.rect().x() - message.rect().width()).abs()
+                < 1e-3
+        );
+        assert!(plain.chart().is_none());
+
+        let truncated = Badge::builder()
+            .message("a long message")
+            .sparkline(Sparkline::new([1.0]).width(30.0))
+            .max_width(60.0)
+            .build();
+        assert!(truncated.layout().segments()[0].rect().width() <= 60.0);
+    }
+
+    #[test]
+    fn test_empty_text() {
+        let layout = Badge::builder().label("build").build().layout();
+        let message = layout.segments()[1];
+        assert!(message.text().is_none());
+        assert!(message.shadow().is_none());
+        assert!((message.rect().width() - 10.0).abs() < f32::EPSILON);
+    }
+}
@@ -23,9 +23,25 @@
 //! let badge = Badge::builder().label("hello").message("world").colour(Colour::GREEN).build();
 //! println!("{}", badge.render());
 //! ```
+mod animation;
 mod badge;
 mod colour;
 mod font;
+pub mod format;
+mod geometry;
+mod group;
+mod hash;
+mod layout;
+#[cfg(feature = "png")]
+mod png;
+#[cfg(feature = "server")]
+mod provider;
+mod scale;
+mod segment;
+#[cfg(feature = "server")]
+mod server;
+mod sparkline;
+mod theme;
 mod traits;
 mod vendor;
 
@@ -35,11 +51,21 @@ const FONT_FAMILY: &str = "Verdana,Geneva,DejaVu Sans,sans-serif";
 const FONT_SCALE_UP_FACTOR: f32 = 10.0;
 const FONT_SCALE_DOWN_VALUE: &str = "scale(.1)";
 const BRIGHTNESS_THRESHOLD: u8 = 176; // 0.69 * 255
-const HEIGHT: f32 = 20.0;
-const VERTICAL_MARGIN: f32 = 0.0;
-const SHADOW: bool = true;
-const LOGO_HEIGHT: f32 = 14.0;
 
-pub use badge::{Badge, Builder as BadgeBuilder};
+pub use animation::Animation;
+pub use badge::{is_valid_id_prefix, Badge, Builder as BadgeBuilder, LogoPosition};
 pub use colour::Colour;
+pub use geometry::Geometry;
+pub use group::{Arrangement, BadgeGroup};
+pub use layout::{Layout, Rect, SegmentLayout, TextBox};
+#[cfg(feature = "png")]
+pub use png::PngError;
+#[cfg(feature = "server")]
+pub use provider::{BadgeProvider, DirectoryProvider, EndpointBadge, MemoryProvider};
+pub use scale::ColourScale;
+pub use segment::{LinkTarget, LogoSide, Segment};
+#[cfg(feature = "server")]
+pub use server::{Server, ServerConfig};
+pub use sparkline::Sparkline;
+pub use theme::Theme;
 pub use traits::Render;
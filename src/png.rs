@@ -0,0 +1,181 @@
+use resvg::{
+    tiny_skia::{Pixmap, Size, Transform},
+    usvg::{fontdb::Database, ImageHrefResolver, Options, Tree},
+};
+use std::{
+    fmt::Display,
+    sync::{Arc, OnceLock},
+};
+
+// DejaVu Sans is part of the font stack badges are rendered with, and is freely redistributable,
+// so it is bundled to make rasterization independent of the fonts installed on the system.
+static FONT_DATA: &[u8] = include_bytes!("vendor/DejaVuSans.ttf");
+
+/// The most pixels a rasterized image may have, which is 64 MiB of pixel data.
+const MAX_PIXELS: u64 = 4096 * 4096;
+
+/// An error that can occur when rendering a PNG image.
+#[derive(Debug)]
+pub enum PngError {
+    /// The rendered SVG could not be parsed.
+    ///
+    /// This usually indicates that a logo is not a valid image.
+    Svg(resvg::usvg::Error),
+    /// The scale is not a positive number, or results in an image that is too large.
+    InvalidScale(f32),
+    /// The image would be larger than the pixel budget, such as because of a very wide logo.
+    TooLarge {
+        /// The width of the image, in pixels.
+        width: u32,
+        /// The height of the image, in pixels.
+        height: u32,
+    },
+    /// The rasterized image could not be encoded as a PNG.
+    Encode(String),
+}
+
+impl Display for PngError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Svg(e) => write!(f, "failed to parse SVG: {e}"),
+            Self::InvalidScale(scale) => write!(f, "invalid scale: {scale}"),
+            Self::TooLarge { width, height } => {
+                write!(f, "image too large: {width}x{height} pixels")
+            }
+            Self::Encode(e) => write!(f, "failed to encode PNG: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for PngError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Svg(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+fn font_database() -> Arc<Database> {
+    static DATABASE: OnceLock<Arc<Database>> = OnceLock::new();
+    DATABASE
+        .get_or_init(|| {
+            let mut database = Database::new();
+            database.load_font_data(FONT_DATA.to_vec());
+            database.set_sans_serif_family("DejaVu Sans");
+            Arc::new(database)
+        })
+        .clone()
+}
+
+/// Rasterize an SVG document to a PNG image, scaled by `scale`.
+///
+/// Only the bundled font is used for text, and only `data:` URIs are loaded as images, so the
+/// output is the same on every machine. Other logos, such as URLs or paths to local files, are
+/// left out. Images of more than [`MAX_PIXELS`] pixels are refused rather than allocated.
+pub fn rasterize(svg: &str, scale: f32) -> Result<Vec<u8>, PngError> {
+    let options = Options {
+        fontdb: font_database(),
+        image_href_resolver: ImageHrefResolver {
+            resolve_data: ImageHrefResolver::default_data_resolver(),
+            resolve_string: Box::new(|_, _| None),
+        },
+        ..Options::default()
+    };
+    let tree = Tree::from_str(svg, &options).map_err(PngError::Svg)?;
+    let size = Size::from_wh(tree.size().width() * scale, tree.size().height() * scale)
+        .ok_or(PngError::InvalidScale(scale))?
+        .to_int_size();
+    if u64::from(size.width()) * u64::from(size.height()) > MAX_PIXELS {
+        return Err(PngError::TooLarge {
+            width: size.width(),
+            height: size.height(),
+        });
+    }
+    let mut pixmap =
+        Pixmap::new(size.width(), size.height()).ok_or(PngError::InvalidScale(scale))?;
+    resvg::render(
+        &tree,
+        Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+    pixmap
+        .encode_png()
+        .map_err(|e| PngError::Encode(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Badge, Render};
+
+    fn dimensions(png: &[u8]) -> (u32, u32) {
+        let ihdr = &png[16..24];
+        (
+            u32::from_be_bytes([ihdr[0], ihdr[1], ihdr[2], ihdr[3]]),
+            u32::from_be_bytes([ihdr[4], ihdr[5], ihdr[6], ihdr[7]]),
+        )
+    }
+
+    #[test]
+    fn test_render_png() {
+        let badge = Badge::builder().label("build").message("passing").build();
+        let png = badge.render_png(2.0).expect("failed to render PNG");
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert_eq!(dimensions(&png), (177, 40));
+        assert_eq!(png, badge.render_png(2.0).expect("failed to render PNG"));
+    }
+
+    #[test]
+    fn test_local_logo_ignored() {
+        let path = std::env::temp_dir().join(format!("badges-logo-{}.svg", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="14" height="14"><rect width="14" height="14" fill="red"/></svg>"#,
+        )
+        .expect("failed to write logo");
+        let render = |logo: &str| {
+            Badge::builder()
+                .message("passing")
+                .logo(logo)
+                .id_prefix("x")
+                .build()
+                .render_png(1.0)
+                .expect("failed to render PNG")
+        };
+        let local = render(path.to_str().expect("temporary path is not UTF-8"));
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(local, render("missing.svg"));
+    }
+
+    #[test]
+    fn test_invalid_scale() {
+        let badge = Badge::builder().message("passing").build();
+        assert!(matches!(
+            badge.render_png(0.0),
+            Err(PngError::InvalidScale(_))
+        ));
+        assert!(matches!(
+            badge.render_png(f32::NAN),
+            Err(PngError::InvalidScale(_))
+        ));
+    }
+
+    #[test]
+    fn test_too_large() {
+        let badge = Badge::builder()
+            .message("passing")
+            .logo("data:,")
+            .logo_width(2_000_000.0)
+            .build();
+        assert!(matches!(
+            badge.render_png(1.0),
+            Err(PngError::TooLarge { height: 20, .. })
+        ));
+        let badge = Badge::builder().message("passing").build();
+        assert!(matches!(
+            badge.render_png(1000.0),
+            Err(PngError::TooLarge { .. })
+        ));
+    }
+}
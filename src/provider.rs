@@ -0,0 +1,304 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::PathBuf,
+    sync::{PoisonError, RwLock},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Colour;
+
+const fn schema_version() -> u8 {
+    1
+}
+
+/// The data of a badge in the [shields.io endpoint](https://shields.io/badges/endpoint-badge)
+/// format.
+///
+/// This is what the [`Server`](crate::Server) renders `/endpoint` and `/b/<name>` badges from.
+///
+/// # Example
+///
+/// ```rust
+/// use badges::{Colour, EndpointBadge};
+/// let badge = EndpointBadge::new("coverage", "91%").colour(Colour::from_colour_code(0x97_ca_00));
+/// let json = badge.to_json();
+/// assert_eq!(EndpointBadge::from_json(&json).ok(), Some(badge));
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointBadge {
+    #[serde(default = "schema_version")]
+    schema_version: u8,
+    #[serde(default)]
+    pub(crate) label: String,
+    pub(crate) message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) label_color: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) is_error: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) logo_svg: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) logo_width: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) cache_seconds: Option<u32>,
+}
+
+impl EndpointBadge {
+    /// Create a new badge with the given label and message.
+    #[must_use]
+    pub fn new(label: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            schema_version: 1,
+            label: label.into(),
+            message: message.into(),
+            color: None,
+            label_color: None,
+            is_error: false,
+            logo_svg: None,
+            logo_width: None,
+            cache_seconds: None,
+        }
+    }
+
+    /// Set the colour of the message.
+    #[must_use]
+    pub fn colour(mut self, colour: Colour) -> Self {
+        self.color = Some(colour.to_string());
+        self
+    }
+
+    /// Set the colour of the label.
+    #[must_use]
+    pub fn label_colour(mut self, label_colour: Colour) -> Self {
+        self.label_color = Some(label_colour.to_string());
+        self
+    }
+
+    /// Mark the badge as reporting an error, which colours it red unless a colour is set.
+    #[must_use]
+    pub const fn error(mut self) -> Self {
+        self.is_error = true;
+        self
+    }
+
+    /// Set the logo, as the source of an SVG image.
+    #[must_use]
+    pub fn logo_svg(mut self, logo_svg: impl Into<String>) -> Self {
+        self.logo_svg = Some(logo_svg.into());
+        self
+    }
+
+    /// Set the logo width.
+    #[must_use]
+    pub const fn logo_width(mut self, logo_width: f32) -> Self {
+        self.logo_width = Some(logo_width);
+        self
+    }
+
+    /// Set how long clients should cache the badge for, in seconds.
+    #[must_use]
+    pub const fn cache_seconds(mut self, cache_seconds: u32) -> Self {
+        self.cache_seconds = Some(cache_seconds);
+        self
+    }
+
+    /// Parse a badge from shields.io endpoint JSON.
+    ///
+    /// `schemaVersion` may be left out, but must be `1` if present.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is invalid or does not follow the endpoint schema.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let badge: Self = serde_json::from_str(json)?;
+        if badge.schema_version != 1 {
+            return Err(serde::de::Error::custom(format!(
+                "unsupported schemaVersion {}",
+                badge.schema_version
+            )));
+        }
+        Ok(badge)
+    }
+
+    /// Serialize the badge as shields.io endpoint JSON.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// A source of the badges served under `/b/` by a [`Server`](crate::Server).
+///
+/// A request for `/b/<name>.svg` renders the badge the provider returns for `<name>`, and a
+/// `POST` of endpoint JSON to `/b/<name>` replaces it. Names are checked by the server before
+/// they reach the provider: they are made of ASCII letters, digits, `-`, `_` and `.`, optionally
+/// split into several parts by `/`, and no part starts with a `.`.
+pub trait BadgeProvider: Send + Sync {
+    /// The badge called `name`, or `None` if there is no such badge.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the badge exists but cannot be read.
+    fn badge(&self, name: &str) -> io::Result<Option<EndpointBadge>>;
+
+    /// Replace the badge called `name`.
+    ///
+    /// Providers are read-only unless they override this.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`io::ErrorKind::Unsupported`] if the provider is read-only, or
+    /// any other error if the badge could not be stored.
+    fn update(&self, name: &str, badge: EndpointBadge) -> io::Result<()> {
+        let _ = (name, badge);
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+/// Whether `name` is a valid name for a badge from a [`BadgeProvider`].
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.split('/').all(|part| {
+            !part.is_empty()
+                && !part.starts_with('.')
+                && part
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b"-_.".contains(&b))
+        })
+}
+
+/// A [`BadgeProvider`] reading badges from the endpoint JSON files in a directory.
+///
+/// The badge called `name` is read from `<name>.json` on every request, so changes to the files
+/// show up straight away. The provider is read-only, so `POST`s to the server cannot change the
+/// files; they are left to whatever generates them, such as a CI job.
+#[derive(Debug, Clone)]
+pub struct DirectoryProvider {
+    root: PathBuf,
+}
+
+impl DirectoryProvider {
+    /// Create a provider reading from the directory at `root`.
+    #[must_use]
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.root.join(format!("{name}.json"))
+    }
+}
+
+impl BadgeProvider for DirectoryProvider {
+    fn badge(&self, name: &str) -> io::Result<Option<EndpointBadge>> {
+        let json = match fs::read_to_string(self.path(name)) {
+            Ok(json) => json,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        EndpointBadge::from_json(&json)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// A [`BadgeProvider`] keeping badges in memory.
+///
+/// Badges are added with [`MemoryProvider::insert`] or by `POST`ing them to the server, and are
+/// lost when the server stops.
+#[derive(Debug, Default)]
+pub struct MemoryProvider {
+    badges: RwLock<HashMap<String, EndpointBadge>>,
+}
+
+impl MemoryProvider {
+    /// Create an empty provider.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace the badge called `name`.
+    pub fn insert(&self, name: impl Into<String>, badge: EndpointBadge) {
+        self.badges
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(name.into(), badge);
+    }
+}
+
+impl BadgeProvider for MemoryProvider {
+    fn badge(&self, name: &str) -> io::Result<Option<EndpointBadge>> {
+        Ok(self
+            .badges
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(name)
+            .cloned())
+    }
+
+    fn update(&self, name: &str, badge: EndpointBadge) -> io::Result<()> {
+        self.insert(name, badge);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_names() {
+        assert!(is_valid_name("coverage"));
+        assert!(is_valid_name("my-repo/bench_1.2"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("../secret"));
+        assert!(!is_valid_name("a//b"));
+        assert!(!is_valid_name(".hidden"));
+        assert!(!is_valid_name("/absolute"));
+        assert!(!is_valid_name("back\\slash"));
+    }
+
+    #[test]
+    fn test_endpoint_json() {
+        let badge = EndpointBadge::from_json(r#"{"label": "coverage", "message": "91%"}"#)
+            .expect("failed to parse");
+        assert_eq!(badge, EndpointBadge::new("coverage", "91%"));
+        assert_eq!(
+            badge.to_json(),
+            r#"{"schemaVersion":1,"label":"coverage","message":"91%"}"#
+        );
+        assert!(EndpointBadge::from_json(r#"{"schemaVersion": 2, "message": "x"}"#).is_err());
+        assert!(EndpointBadge::from_json(r#"{"label": "no message"}"#).is_err());
+    }
+
+    #[test]
+    fn test_directory_provider() {
+        let root = std::env::temp_dir().join(format!("badges-provider-{}", std::process::id()));
+        let provider = DirectoryProvider::new(&root);
+        assert!(provider
+            .badge("coverage")
+            .expect("failed to read")
+            .is_none());
+        let badge = EndpointBadge::new("coverage", "91%");
+        fs::create_dir_all(root.join("repo")).expect("failed to create");
+        fs::write(root.join("repo/coverage.json"), badge.to_json()).expect("failed to write");
+        assert_eq!(
+            provider.badge("repo/coverage").expect("failed to read"),
+            Some(badge.clone())
+        );
+        fs::write(root.join("broken.json"), "{").expect("failed to write");
+        assert!(provider.badge("broken").is_err());
+        let error = provider
+            .update("coverage", badge)
+            .expect_err("directory provider is read-only");
+        assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+        assert!(!root.join("coverage.json").exists());
+        fs::remove_dir_all(&root).expect("failed to remove");
+    }
+}
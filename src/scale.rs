@@ -0,0 +1,176 @@
+use crate::colour::{Colour, BRIGHT_GREEN, GREEN, RED, YELLOW, YELLOW_GREEN};
+
+/// A mapping from numbers to colours, such as red for low test coverage and green for high.
+///
+/// A scale starts with a base colour for low values, and has any number of thresholds above
+/// which a different colour is used. By default the colour changes abruptly at each threshold,
+/// like the colour scales of shields.io. An [interpolated](ColourScale::interpolated) scale
+/// instead blends from each threshold's colour into the next, reaching it exactly at its
+/// threshold.
+///
+/// # Example
+///
+/// ```rust
+/// use badges::{Colour, ColourScale};
+/// let latency = ColourScale::new(Colour::GREEN)
+///     .threshold(100.0, Colour::ORANGE)
+///     .threshold(500.0, Colour::RED);
+/// assert_eq!(latency.colour(42.0), Colour::GREEN);
+/// assert_eq!(latency.colour(250.0), Colour::ORANGE);
+/// assert_eq!(latency.colour(1000.0), Colour::RED);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColourScale {
+    base: Colour,
+    /// Thresholds and their colours, sorted by threshold.
+    thresholds: Vec<(f64, Colour)>,
+    interpolated: bool,
+}
+
+impl ColourScale {
+    /// Create a scale that gives `base` for every value until thresholds are added.
+    #[must_use]
+    pub const fn new(base: Colour) -> Self {
+        Self {
+            base,
+            thresholds: Vec::new(),
+            interpolated: false,
+        }
+    }
+
+    /// Use `colour` for values at or above `at`, up to the next threshold.
+    ///
+    /// Thresholds may be added in any order. A NaN threshold is ignored.
+    #[must_use]
+    pub fn threshold(mut self, at: f64, colour: Colour) -> Self {
+        if !at.is_nan() {
+            let index = self.thresholds.partition_point(|&(t, _)| t <= at);
+            self.thresholds.insert(index, (at, colour));
+        }
+        self
+    }
+
+    /// Blend between the colours of neighbouring thresholds instead of stepping between them.
+    ///
+    /// Values below the first threshold still get the base colour, and values above the last
+    /// threshold get its colour.
+    #[must_use]
+    pub const fn interpolated(mut self) -> Self {
+        self.interpolated = true;
+        self
+    }
+
+    /// The shields.io scale for test coverage percentages.
+    ///
+    /// Coverage below 50% is red, then yellow, yellowgreen from 80%, green from 90%, and
+    /// brightgreen at 100%.
+    #[must_use]
+    pub fn coverage() -> Self {
+        Self::new(RED)
+            .threshold(50.0, YELLOW)
+            .threshold(80.0, YELLOW_GREEN)
+            .threshold(90.0, GREEN)
+            .threshold(100.0, BRIGHT_GREEN)
+    }
+
+    /// The shields.io scale for download counts.
+    ///
+    /// No downloads is red, then yellow, yellowgreen from 10, green from 100, and brightgreen
+    /// from 1000.
+    #[must_use]
+    pub fn downloads() -> Self {
+        Self::new(RED)
+            .threshold(1.0, YELLOW)
+            .threshold(10.0, YELLOW_GREEN)
+            .threshold(100.0, GREEN)
+            .threshold(1000.0, BRIGHT_GREEN)
+    }
+
+    /// A continuous scale for percentages, from red at 0% through yellow at 50% to
+    /// brightgreen at 100%.
+    #[must_use]
+    pub fn percentage() -> Self {
+        Self::new(RED)
+            .threshold(0.0, RED)
+            .threshold(50.0, YELLOW)
+            .threshold(100.0, BRIGHT_GREEN)
+            .interpolated()
+    }
+
+    /// The colour for `value`.
+    ///
+    /// A NaN value gets the base colour.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn colour(&self, value: f64) -> Colour {
+        let index = self.thresholds.partition_point(|&(t, _)| t <= value);
+        let Some(&(below, below_colour)) = index.checked_sub(1).map(|i| &self.thresholds[i]) else {
+            return self.base;
+        };
+        match self.thresholds.get(index) {
+            Some(&(above, above_colour)) if self.interpolated => {
+                below_colour.mix(above_colour, ((value - below) / (above - below)) as f32)
+            }
+            _ => below_colour,
+        }
+    }
+}
+
+impl Default for ColourScale {
+    /// The [coverage](ColourScale::coverage) scale.
+    fn default() -> Self {
+        Self::coverage()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps() {
+        let scale = ColourScale::coverage();
+        assert_eq!(scale.colour(-1.0), RED);
+        assert_eq!(scale.colour(49.9), RED);
+        assert_eq!(scale.colour(50.0), YELLOW);
+        assert_eq!(scale.colour(85.0), YELLOW_GREEN);
+        assert_eq!(scale.colour(91.3), GREEN);
+        assert_eq!(scale.colour(100.0), BRIGHT_GREEN);
+        assert_eq!(scale.colour(f64::NAN), RED);
+    }
+
+    #[test]
+    fn test_threshold_order() {
+        let a = ColourScale::new(Colour::BLACK)
+            .threshold(10.0, Colour::RED)
+            .threshold(1.0, Colour::BLUE);
+        let b = ColourScale::new(Colour::BLACK)
+            .threshold(1.0, Colour::BLUE)
+            .threshold(10.0, Colour::RED);
+        assert_eq!(a, b);
+        assert_eq!(a.colour(5.0), Colour::BLUE);
+    }
+
+    #[test]
+    fn test_interpolated() {
+        let scale = ColourScale::new(Colour::BLACK)
+            .threshold(0.0, Colour::BLACK)
+            .threshold(100.0, Colour::WHITE)
+            .interpolated();
+        assert_eq!(scale.colour(-5.0), Colour::BLACK);
+        assert_eq!(scale.colour(50.0), Colour::from_rgb(128, 128, 128));
+        assert_eq!(scale.colour(100.0), Colour::WHITE);
+        assert_eq!(scale.colour(1e9), Colour::WHITE);
+
+        let percentage = ColourScale::percentage();
+        assert_eq!(percentage.colour(50.0), YELLOW);
+        assert_eq!(percentage.colour(75.0), YELLOW.mix(BRIGHT_GREEN, 0.5));
+    }
+
+    #[test]
+    fn test_shields_names() {
+        assert_eq!(Colour::from_shields_name("brightgreen"), Some(BRIGHT_GREEN));
+        assert_eq!(Colour::from_shields_name("Critical"), Some(RED));
+        assert_eq!(Colour::from_shields_name("purple"), None);
+    }
+}
@@ -0,0 +1,413 @@
+use std::{
+    borrow::Cow,
+    hash::{Hash, Hasher},
+    time::Duration,
+};
+
+use crate::{
+    animation::Animation, colour::Colour, geometry::Geometry, layout, sparkline::Sparkline,
+    traits::IteratorExt,
+};
+
+/// Which end of a [`Segment`] its logo sits at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LogoSide {
+    /// Before the text of the segment. This is the default.
+    #[default]
+    Start,
+    /// After the text of the segment.
+    End,
+}
+
+/// Where a linked [`Segment`] opens when clicked.
+///
+/// Badges embedded with `<object>` or `<iframe>` are rendered in their own browsing context, so
+/// a link without a target would navigate inside the embedded badge rather than the page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LinkTarget {
+    /// Open the link in a new tab or window. This is the default.
+    #[default]
+    Blank,
+    /// Open the link in the top-level browsing context, replacing the embedding page.
+    Top,
+    /// Open the link in the parent of the browsing context the badge is embedded in.
+    Parent,
+    /// Open the link in the browsing context the badge is rendered in.
+    Current,
+}
+
+impl LinkTarget {
+    pub(crate) const fn as_str(self) -> &'static str {
+        match self {
+            Self::Blank => "_blank",
+            Self::Top => "_top",
+            Self::Parent => "_parent",
+            Self::Current => "_self",
+        }
+    }
+}
+
+/// A single coloured panel of a [`Badge`](crate::Badge).
+///
+/// A badge is an ordered list of segments, drawn from left to right. The label and message set
+/// on a [`BadgeBuilder`](crate::BadgeBuilder) are segments themselves, and any number of
+/// additional segments can be appended with [`BadgeBuilder::segment`](crate::BadgeBuilder::segment).
+///
+/// # Example
+///
+/// ```rust
+/// use badges::{Badge, Colour, Render, Segment};
+/// let badge = Badge::builder()
+///     .label("build")
+///     .message("passing")
+///     .segment(Segment::new("2m 31s").colour(Colour::SLATE_GRAY))
+///     .build();
+/// println!("{}", badge.render());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Segment<'a> {
+    pub(crate) text: Cow<'a, str>,
+    /// The text before it was truncated, if it was.
+    pub(crate) full_text: Option<Cow<'a, str>>,
+    pub(crate) max_width: Option<f32>,
+    pub(crate) max_chars: Option<usize>,
+    pub(crate) multi_line: bool,
+    pub(crate) colour: Colour,
+    pub(crate) dark_colour: Option<Colour>,
+    pub(crate) logo: Option<&'a str>,
+    pub(crate) logo_width: Option<f32>,
+    pub(crate) logo_side: LogoSide,
+    pub(crate) link: Option<&'a str>,
+    pub(crate) link_target: LinkTarget,
+    pub(crate) animation: Option<Animation>,
+    /// The segments whose text is shown after this one's when cycling, each for `cycle_period`.
+    pub(crate) cycle: Vec<Self>,
+    pub(crate) cycle_period: Duration,
+    /// The fraction of the segment filled with its colour, if it is a progress bar.
+    pub(crate) progress: Option<f32>,
+    pub(crate) track_colour: Option<Colour>,
+    pub(crate) dark_track_colour: Option<Colour>,
+    pub(crate) sparkline: Option<Sparkline>,
+}
+
+impl<'a> Segment<'a> {
+    /// Create a new segment with the given text.
+    ///
+    /// The segment is coloured `#555555` unless [`Segment::colour`] is called.
+    #[must_use]
+    pub fn new(text: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            text: text.into(),
+            full_text: None,
+            max_width: None,
+            max_chars: None,
+            multi_line: false,
+            colour: Colour::from_colour_code(0x55_55_55),
+            dark_colour: None,
+            logo: None,
+            logo_width: None,
+            logo_side: LogoSide::Start,
+            link: None,
+            link_target: LinkTarget::Blank,
+            animation: None,
+            cycle: Vec::new(),
+            cycle_period: Duration::ZERO,
+            progress: None,
+            track_colour: None,
+            dark_track_colour: None,
+            sparkline: None,
+        }
+    }
+
+    /// Set the background colour of the segment.
+    #[must_use]
+    pub const fn colour(mut self, colour: Colour) -> Self {
+        self.colour = colour;
+        self
+    }
+
+    /// Set the background colour of the segment when the viewer prefers a dark colour scheme.
+    ///
+    /// The text colour follows the background, as it does for [`Segment::colour`]. This relies on
+    /// the `prefers-color-scheme` CSS media query, which applies wherever the SVG is shown,
+    /// including in `<img>` elements.
+    #[must_use]
+    pub const fn dark_colour(mut self, dark_colour: Colour) -> Self {
+        self.dark_colour = Some(dark_colour);
+        self
+    }
+
+    /// Set the logo for the segment.
+    #[must_use]
+    pub const fn logo(mut self, logo: &'a str) -> Self {
+        self.logo = Some(logo);
+        self
+    }
+
+    /// Set the logo width for the segment.
+    #[must_use]
+    pub const fn logo_width(mut self, logo_width: f32) -> Self {
+        self.logo_width = Some(logo_width);
+        self
+    }
+
+    /// Set which end of the segment the logo is placed at.
+    ///
+    /// Defaults to [`LogoSide::Start`].
+    #[must_use]
+    pub const fn logo_side(mut self, logo_side: LogoSide) -> Self {
+        self.logo_side = logo_side;
+        self
+    }
+
+    /// Set the URL the segment links to.
+    ///
+    /// Linked segments are only clickable when the badge is embedded with `<object>`,
+    /// `<iframe>` or inlined into the page, as browsers do not make `<img>` contents interactive.
+    #[must_use]
+    pub const fn link(mut self, link: &'a str) -> Self {
+        self.link = Some(link);
+        self
+    }
+
+    /// Set where the segment's link opens.
+    ///
+    /// Defaults to [`LinkTarget::Blank`].
+    #[must_use]
+    pub const fn link_target(mut self, link_target: LinkTarget) -> Self {
+        self.link_target = link_target;
+        self
+    }
+
+    /// Set the maximum width of the segment, including its padding and logo.
+    ///
+    /// Longer text is cut short with an ellipsis when the badge is built. The badge's title and
+    /// `aria-label` keep the full text.
+    #[must_use]
+    pub const fn max_width(mut self, max_width: f32) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    /// Set the maximum number of characters in the segment's text, including the ellipsis added
+    /// if it is cut short.
+    ///
+    /// Characters are counted as graphemes, so an accented letter made of several code points
+    /// counts once. The badge's title and `aria-label` keep the full text.
+    #[must_use]
+    pub const fn max_chars(mut self, max_chars: usize) -> Self {
+        self.max_chars = Some(max_chars);
+        self
+    }
+
+    /// Split the text of the segment into lines at newlines, and wrap it to fit the segment's
+    /// [maximum width](Segment::max_width) if it has one, making the badge taller as needed.
+    ///
+    /// Text that wraps is broken at spaces, or between characters for words too wide to fit a
+    /// line of their own. Lines are centred, and the badge's title and `aria-label` join them
+    /// with spaces.
+    #[must_use]
+    pub const fn multi_line(mut self) -> Self {
+        self.multi_line = true;
+        self
+    }
+
+    /// Animate the segment. Segments are static unless this is called.
+    #[must_use]
+    pub const fn animation(mut self, animation: Animation) -> Self {
+        self.animation = Some(animation);
+        self
+    }
+
+    /// Cycle the text of the segment through each of `texts` after its own, showing each for
+    /// `period`, such as to rotate through supported versions.
+    ///
+    /// The segment is as wide as the widest of its texts, and each is cut short or wrapped like
+    /// the segment's own. Renderers without animation support show only the segment's own
+    /// text, while the badge's title and `aria-label` list them all.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use badges::{Badge, Render, Segment};
+    /// let badge = Badge::builder()
+    ///     .label("python")
+    ///     .message("supported")
+    ///     .segment(Segment::new("3.12").cycle(["3.13", "3.14"], Duration::from_secs(2)))
+    ///     .build();
+    /// assert!(badge.render().contains("<title>python: supported, 3.12 / 3.13 / 3.14</title>"));
+    /// ```
+    #[must_use]
+    pub fn cycle<T: Into<Cow<'a, str>>>(
+        mut self,
+        texts: impl IntoIterator<Item = T>,
+        period: Duration,
+    ) -> Self {
+        self.cycle = texts.into_iter().map(Self::new).collect();
+        self.cycle_period = period;
+        self
+    }
+
+    /// Draw the segment as a progress bar, filled with its colour from the left for `fraction`
+    /// of its width, and with its [track colour](Segment::track_colour) for the rest.
+    ///
+    /// `fraction` is clamped to between `0.0` and `1.0`, and NaN draws no bar at all. The text
+    /// colour is chosen to be readable over both parts.
+    #[must_use]
+    pub const fn progress(mut self, fraction: f32) -> Self {
+        self.progress = if fraction.is_nan() {
+            None
+        } else {
+            Some(fraction.clamp(0.0, 1.0))
+        };
+        self
+    }
+
+    /// Set the colour of the unfilled part of a [progress bar](Segment::progress).
+    ///
+    /// Defaults to the light grey shields.io uses for inactive badges.
+    #[must_use]
+    pub const fn track_colour(mut self, track_colour: Colour) -> Self {
+        self.track_colour = Some(track_colour);
+        self
+    }
+
+    /// Set the colour of the unfilled part of a [progress bar](Segment::progress) when the
+    /// viewer prefers a dark colour scheme.
+    ///
+    /// See [`Segment::dark_colour`].
+    #[must_use]
+    pub const fn dark_track_colour(mut self, dark_track_colour: Colour) -> Self {
+        self.dark_track_colour = Some(dark_track_colour);
+        self
+    }
+
+    /// Draw a [`Sparkline`] chart after the text of the segment, making it wider to fit.
+    #[must_use]
+    pub fn sparkline(mut self, sparkline: Sparkline) -> Self {
+        self.sparkline = Some(sparkline);
+        self
+    }
+
+    /// Cut the text short if it is over the maximum number of characters, or the maximum width
+    /// for single-line segments, and wrap multi-line segments to their maximum width. The texts
+    /// the segment cycles through are shortened the same way.
+    pub(crate) fn truncate(&mut self, geometry: &Geometry) {
+        let cycle = std::mem::take(&mut self.cycle);
+        self.truncate_text(geometry);
+        let cycle = cycle
+            .into_iter()
+            .map(|frame| {
+                let mut frame = Self {
+                    text: frame.text,
+                    full_text: None,
+                    ..self.clone()
+                };
+                frame.truncate_text(geometry);
+                frame
+            })
+            .collect();
+        self.cycle = cycle;
+    }
+
+    fn truncate_text(&mut self, geometry: &Geometry) {
+        if let Some(truncated) = layout::truncate(self, geometry) {
+            self.full_text = Some(std::mem::replace(&mut self.text, truncated.into()));
+        }
+        if let Some(wrapped) = layout::wrap(self, geometry) {
+            let text = std::mem::replace(&mut self.text, wrapped.into());
+            self.full_text.get_or_insert(text);
+        }
+    }
+
+    /// The text of the segment before it was truncated or wrapped, as read out by screen readers.
+    ///
+    /// The texts of cycling segments are separated by slashes.
+    pub(crate) fn full_text(&self) -> Cow<'_, str> {
+        if self.cycle.is_empty() {
+            return self.own_full_text();
+        }
+        Cow::Owned(self.frames().map(Self::own_full_text).join(" / "))
+    }
+
+    fn own_full_text(&self) -> Cow<'_, str> {
+        let text = self.full_text.as_deref().unwrap_or(&self.text);
+        if self.multi_line && text.contains('\n') {
+            Cow::Owned(text.replace('\n', " "))
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
+
+    /// The lines of text in the segment.
+    pub(crate) fn lines(&self) -> impl Iterator<Item = &str> {
+        let lines = if self.multi_line { usize::MAX } else { 1 };
+        self.text.splitn(lines, '\n')
+    }
+
+    /// The number of lines of text in the segment.
+    pub(crate) fn line_count(&self) -> usize {
+        self.lines().count()
+    }
+
+    /// The segment followed by the segments holding the texts it cycles through.
+    pub(crate) fn frames(&self) -> impl Iterator<Item = &Self> {
+        std::iter::once(self).chain(&self.cycle)
+    }
+
+    pub(crate) const fn has_logo(&self) -> bool {
+        matches!(self.logo, Some(l) if !l.is_empty())
+    }
+
+    /// Whether any part of the segment changes colour when the viewer prefers a dark colour
+    /// scheme.
+    pub(crate) const fn has_dark_colours(&self) -> bool {
+        self.dark_colour.is_some() || (self.progress.is_some() && self.dark_track_colour.is_some())
+    }
+
+    /// The width of the segment's sparkline, or zero if it has none.
+    pub(crate) fn chart_width(&self, geometry: &Geometry) -> f32 {
+        self.sparkline
+            .as_ref()
+            .map_or(0.0, |sparkline| sparkline.width_or_default(geometry))
+    }
+
+    /// The width of the logo, which is `default` unless the segment sets its own.
+    pub(crate) const fn logo_width_or(&self, default: f32) -> f32 {
+        match self.logo_width {
+            Some(w) => w,
+            None => {
+                if self.has_logo() {
+                    default
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+impl Hash for Segment<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.text.hash(state);
+        self.full_text.hash(state);
+        self.max_width.map(f32::to_bits).hash(state);
+        self.max_chars.hash(state);
+        self.multi_line.hash(state);
+        self.colour.hash(state);
+        self.dark_colour.hash(state);
+        self.logo.hash(state);
+        self.logo_width.map(f32::to_bits).hash(state);
+        self.logo_side.hash(state);
+        self.link.hash(state);
+        self.link_target.hash(state);
+        self.animation.hash(state);
+        self.cycle.hash(state);
+        self.cycle_period.hash(state);
+        self.progress.map(f32::to_bits).hash(state);
+        self.track_colour.hash(state);
+        self.dark_track_colour.hash(state);
+        self.sparkline.hash(state);
+    }
+}
@@ -0,0 +1,778 @@
+use std::{
+    hash::Hasher,
+    io::{self, Read},
+    net::{IpAddr, SocketAddr, ToSocketAddrs},
+    sync::Arc,
+    time::Duration,
+};
+
+use tiny_http::{Header, Method, Request, Response};
+use ureq::http::{uri::Authority, Uri};
+
+use crate::{
+    hash::Fnv1a,
+    provider::{is_valid_name, BadgeProvider, EndpointBadge},
+    Badge, Colour, Render,
+};
+
+/// The largest request body accepted when updating a badge.
+const MAX_BODY_SIZE: u64 = 64 * 1024;
+
+/// The widest logo rendered, so that requests cannot make the server lay out and rasterize huge
+/// images.
+const MAX_LOGO_WIDTH: f32 = 100.0;
+
+/// Configuration of a [`Server`].
+///
+/// # Example
+///
+/// ```rust
+/// use badges::ServerConfig;
+/// let config = ServerConfig::new()
+///     .allow_host("ci.example.com")
+///     .cache_seconds(60);
+/// ```
+#[derive(Clone)]
+pub struct ServerConfig {
+    allowed_hosts: Vec<String>,
+    cache_seconds: u32,
+    timeout: Duration,
+    provider: Option<Arc<dyn BadgeProvider>>,
+    update_token: Option<String>,
+}
+
+impl std::fmt::Debug for ServerConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServerConfig")
+            .field("allowed_hosts", &self.allowed_hosts)
+            .field("cache_seconds", &self.cache_seconds)
+            .field("timeout", &self.timeout)
+            .field("provider", &self.provider.is_some())
+            .field("update_token", &self.update_token.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ServerConfig {
+    /// Create a configuration that caches badges for five minutes and fetches from no hosts.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            allowed_hosts: Vec::new(),
+            cache_seconds: 300,
+            timeout: Duration::from_secs(5),
+            provider: None,
+            update_token: None,
+        }
+    }
+
+    /// Allow `/endpoint` badges to be fetched from `host`.
+    ///
+    /// The host may include a port, e.g. `localhost:8000` or `[::1]:8000`, in which case only
+    /// that port is allowed. Endpoint badges are refused for every host that has not been
+    /// allowed, and redirects are not followed, so an allowed host cannot send the server
+    /// elsewhere.
+    #[must_use]
+    pub fn allow_host(mut self, host: impl Into<String>) -> Self {
+        self.allowed_hosts.push(host.into());
+        self
+    }
+
+    /// Set how long clients may cache badges for, in seconds.
+    ///
+    /// Endpoints can ask for a longer time with `cacheSeconds`, but not a shorter one.
+    #[must_use]
+    pub const fn cache_seconds(mut self, cache_seconds: u32) -> Self {
+        self.cache_seconds = cache_seconds;
+        self
+    }
+
+    /// Set how long to wait for an endpoint to respond.
+    #[must_use]
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Serve the badges of `provider` under `/b/`.
+    #[must_use]
+    pub fn provider(mut self, provider: impl BadgeProvider + 'static) -> Self {
+        self.provider = Some(Arc::new(provider));
+        self
+    }
+
+    /// Require `POST`s updating badges to send `Authorization: Bearer <token>`.
+    ///
+    /// Without a token, anyone who can reach the server can update badges.
+    #[must_use]
+    pub fn update_token(mut self, token: impl Into<String>) -> Self {
+        self.update_token = Some(token.into());
+        self
+    }
+
+    fn is_allowed(&self, url: &str) -> bool {
+        let Ok(uri) = url.parse::<Uri>() else {
+            return false;
+        };
+        if !matches!(uri.scheme_str(), Some("http" | "https")) {
+            return false;
+        }
+        self.allowed_hosts
+            .iter()
+            .any(|allowed| matches_host(allowed, &uri))
+    }
+}
+
+/// Whether `uri` is on the host `allowed`, which is a host name or IP address with an optional
+/// port, such as `localhost:8000`, `::1` or `[::1]:8000`.
+fn matches_host(allowed: &str, uri: &Uri) -> bool {
+    let Some(host) = uri.host() else {
+        return false;
+    };
+    let authority;
+    // a bare IPv6 address would otherwise be mistaken for a host and port
+    let (allowed_host, allowed_port) = if allowed.parse::<IpAddr>().is_ok() {
+        (allowed, None)
+    } else {
+        let Ok(parsed) = allowed.parse::<Authority>() else {
+            return false;
+        };
+        authority = parsed;
+        (authority.host(), authority.port_u16())
+    };
+    let (allowed_host, host) = (unbracket(allowed_host), unbracket(host));
+    let same_host = match (allowed_host.parse::<IpAddr>(), host.parse::<IpAddr>()) {
+        (Ok(allowed_ip), Ok(ip)) => allowed_ip == ip,
+        _ => allowed_host.eq_ignore_ascii_case(host),
+    };
+    same_host && allowed_port.is_none_or(|port| uri.port_u16() == Some(port))
+}
+
+/// An HTTP server rendering badges from [shields.io](https://shields.io) style URLs.
+///
+/// The following routes are served:
+///
+/// - `/badge/<label>-<message>-<colour>` and `/badge/<message>-<colour>`, where `--` stands for
+///   a dash, `__` for an underscore and `_` for a space, as on shields.io.
+/// - `/endpoint?url=<url>`, which renders the JSON at `url` following the shields.io endpoint
+///   schema. Only hosts allowed with [`ServerConfig::allow_host`] are fetched from.
+/// - `/b/<name>`, which renders the badge called `name` from the [`BadgeProvider`] set with
+///   [`ServerConfig::provider`]. `POST`ing endpoint JSON to the same path replaces the badge,
+///   for providers that can be updated such as [`MemoryProvider`](crate::MemoryProvider).
+///
+/// Every route may end with `.svg`, `.json` or `.png`, and accepts the `label`, `color`,
+/// `labelColor`, `logo`, `logoWidth` and `link` query parameters. Colours are hex codes or
+/// shields.io colour names such as `brightgreen`, logos are `data:` URIs or web URLs, and logo
+/// widths are at most 100 pixels.
+///
+/// Requests are handled one at a time, so this is meant for serving badges to a handful of
+/// clients, such as behind a caching proxy.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use badges::{Server, ServerConfig};
+/// let server = Server::bind("127.0.0.1:8080", ServerConfig::new())?;
+/// server.run();
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct Server {
+    http: tiny_http::Server,
+    config: ServerConfig,
+    agent: ureq::Agent,
+}
+
+impl std::fmt::Debug for Server {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Server")
+            .field("addr", &self.local_addr())
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The format a badge is served in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Svg,
+    Json,
+    Png,
+}
+
+/// The parts of a badge, collected from the URL and endpoint before the badge is rendered.
+#[derive(Debug, Default)]
+struct Params {
+    label: String,
+    message: String,
+    colour: Option<Colour>,
+    label_colour: Option<Colour>,
+    logo: Option<String>,
+    logo_width: Option<f32>,
+    links: Vec<String>,
+    /// How long the endpoint asked for the badge to be cached for.
+    cache_seconds: Option<u32>,
+}
+
+/// A response, before it is turned into a [`tiny_http::Response`].
+#[derive(Debug)]
+struct Reply {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+    cache_seconds: u32,
+}
+
+impl Server {
+    /// Start listening for requests on `addr`.
+    ///
+    /// Requests are not handled until [`Server::run`] is called.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address cannot be bound.
+    pub fn bind(addr: impl ToSocketAddrs, config: ServerConfig) -> io::Result<Self> {
+        let http = tiny_http::Server::http(addr).map_err(io::Error::other)?;
+        // redirects are not followed, as only the first URL is checked against the allowed hosts
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(config.timeout))
+            .max_redirects(0)
+            .build()
+            .into();
+        Ok(Self {
+            http,
+            config,
+            agent,
+        })
+    }
+
+    /// The address the server is listening on.
+    #[must_use]
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.http.server_addr().to_ip()
+    }
+
+    /// Handle requests until [`Server::shutdown`] is called.
+    pub fn run(&self) {
+        for request in self.http.incoming_requests() {
+            self.handle(request);
+        }
+    }
+
+    /// Stop a call to [`Server::run`] once it has finished the current request.
+    pub fn shutdown(&self) {
+        self.http.unblock();
+    }
+
+    fn handle(&self, mut request: Request) {
+        let reply = match request.method() {
+            Method::Get | Method::Head => self.route(request.url()),
+            Method::Post => self.update(&mut request),
+            _ => Reply::text(405, "method not allowed"),
+        };
+        let etag = {
+            let mut hasher = Fnv1a::default();
+            hasher.write(&reply.body);
+            format!("\"{:016x}\"", hasher.finish())
+        };
+        let not_modified = reply.status == 200
+            && request
+                .headers()
+                .iter()
+                .any(|h| h.field.equiv("If-None-Match") && h.value.as_str() == etag);
+        let cache_control = format!("max-age={}", reply.cache_seconds);
+        let headers = [
+            header("Content-Type", reply.content_type),
+            header("Cache-Control", &cache_control),
+            header("ETag", &etag),
+        ];
+        let response = if not_modified {
+            Response::empty(304).boxed()
+        } else {
+            Response::from_data(reply.body)
+                .with_status_code(reply.status)
+                .boxed()
+        };
+        let response = headers
+            .into_iter()
+            .flatten()
+            .fold(response, Response::with_header);
+        // the client may have gone away, which there is nobody to report to
+        let _ = request.respond(response);
+    }
+
+    fn route(&self, url: &str) -> Reply {
+        let (path, query) = url.split_once('?').unwrap_or((url, ""));
+        let (path, format) = split_format(path);
+        let query = parse_query(query);
+        let mut params = if let Some(content) = path.strip_prefix("/badge/") {
+            match percent_decode(content).as_deref().and_then(parse_static) {
+                Some(params) => params,
+                None => {
+                    return Params::error("404", "badge not found").reply(format, 404, 0);
+                }
+            }
+        } else if path == "/endpoint" {
+            let Some(url) = query.iter().find(|(k, _)| k == "url").map(|(_, v)| v) else {
+                return Params::error("custom badge", "invalid query parameter: url")
+                    .reply(format, 400, 0);
+            };
+            if !self.config.is_allowed(url) {
+                return Params::error("custom badge", "host not allowed").reply(format, 403, 0);
+            }
+            match self.fetch(url) {
+                Ok(params) => params,
+                Err(message) => {
+                    return Params::error("custom badge", message).reply(format, 502, 0);
+                }
+            }
+        } else if let Some(name) = path.strip_prefix("/b/") {
+            let Some(provider) = &self.config.provider else {
+                return Params::error("404", "badge not found").reply(format, 404, 0);
+            };
+            match percent_decode(name).filter(|name| is_valid_name(name)) {
+                Some(name) => match provider.badge(&name) {
+                    Ok(Some(badge)) => Params::from(badge),
+                    Ok(None) => {
+                        return Params::error("404", "badge not found").reply(format, 404, 0);
+                    }
+                    Err(_) => return Params::error(&name, "unavailable").reply(format, 500, 0),
+                },
+                None => return Params::error("404", "badge not found").reply(format, 404, 0),
+            }
+        } else {
+            return Params::error("404", "badge not found").reply(format, 404, 0);
+        };
+        if let Err(message) = params.apply_query(&query) {
+            return Params::error("custom badge", message).reply(format, 400, 0);
+        }
+        let cache_seconds = params.cache_seconds.map_or(self.config.cache_seconds, |s| {
+            s.max(self.config.cache_seconds)
+        });
+        params.reply(format, 200, cache_seconds)
+    }
+
+    fn fetch(&self, url: &str) -> Result<Params, &'static str> {
+        let mut response = self.agent.get(url).call().map_err(|_| "inaccessible")?;
+        if response.status().is_redirection() {
+            return Err("inaccessible");
+        }
+        let json = response
+            .body_mut()
+            .read_to_string()
+            .map_err(|_| "invalid response data")?;
+        EndpointBadge::from_json(&json)
+            .map(Params::from)
+            .map_err(|_| "invalid response data")
+    }
+
+    /// Replace a badge of the provider with the endpoint JSON in the body of `request`.
+    fn update(&self, request: &mut Request) -> Reply {
+        let Some(provider) = &self.config.provider else {
+            return Reply::text(405, "method not allowed");
+        };
+        let Some(name) = request
+            .url()
+            .strip_prefix("/b/")
+            .and_then(percent_decode)
+            .filter(|name| is_valid_name(name))
+        else {
+            return Reply::text(404, "not found");
+        };
+        if let Some(token) = &self.config.update_token {
+            let authorized = request.headers().iter().any(|h| {
+                h.field.equiv("Authorization")
+                    && h.value
+                        .as_str()
+                        .strip_prefix("Bearer ")
+                        .is_some_and(|bearer| constant_time_eq(bearer, token))
+            });
+            if !authorized {
+                return Reply::text(401, "unauthorized");
+            }
+        }
+        let mut json = String::new();
+        if request
+            .as_reader()
+            .take(MAX_BODY_SIZE + 1)
+            .read_to_string(&mut json)
+            .is_err()
+        {
+            return Reply::text(400, "body is not UTF-8");
+        }
+        if json.len() as u64 > MAX_BODY_SIZE {
+            return Reply::text(413, "body too large");
+        }
+        let badge = match EndpointBadge::from_json(&json) {
+            Ok(badge) => badge,
+            Err(e) => return Reply::text(400, &e.to_string()),
+        };
+        match provider.update(&name, badge) {
+            Ok(()) => Reply::text(204, ""),
+            Err(e) if e.kind() == io::ErrorKind::Unsupported => {
+                Reply::text(405, "badges cannot be updated")
+            }
+            Err(e) => Reply::text(500, &e.to_string()),
+        }
+    }
+}
+
+impl From<EndpointBadge> for Params {
+    fn from(badge: EndpointBadge) -> Self {
+        let colour = badge.color.as_deref().and_then(parse_colour);
+        Self {
+            label: badge.label,
+            message: badge.message,
+            colour: colour.or_else(|| {
+                badge
+                    .is_error
+                    .then(|| Colour::from_shields_name("red"))
+                    .flatten()
+            }),
+            label_colour: badge.label_color.as_deref().and_then(parse_colour),
+            logo: badge
+                .logo_svg
+                .map(|svg| format!("data:image/svg+xml,{}", percent_encode(&svg))),
+            logo_width: badge.logo_width.and_then(clamp_logo_width),
+            links: Vec::new(),
+            cache_seconds: badge.cache_seconds,
+        }
+    }
+}
+
+impl Params {
+    fn error(label: &str, message: &str) -> Self {
+        Self {
+            label: label.to_owned(),
+            message: message.to_owned(),
+            colour: Colour::from_shields_name("lightgrey"),
+            ..Self::default()
+        }
+    }
+
+    /// Override the parts of the badge given in the query string.
+    ///
+    /// Logos must be `data:` URIs or web URLs, so that paths to files on the server are never
+    /// read when rendering PNGs.
+    fn apply_query(&mut self, query: &[(String, String)]) -> Result<(), &'static str> {
+        for (key, value) in query {
+            match key.as_str() {
+                "label" => self.label.clone_from(value),
+                "color" | "colour" => self.colour = parse_colour(value).or(self.colour),
+                "labelColor" | "labelColour" => {
+                    self.label_colour = parse_colour(value).or(self.label_colour);
+                }
+                "logo" if is_valid_logo(value) => self.logo = Some(value.clone()),
+                "logo" => return Err("invalid query parameter: logo"),
+                "logoWidth" => {
+                    let logo_width = value.parse().ok().and_then(clamp_logo_width);
+                    self.logo_width = Some(logo_width.ok_or("invalid query parameter: logoWidth")?);
+                }
+                "link" => self.links.push(value.clone()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn reply(&self, format: Format, status: u16, cache_seconds: u32) -> Reply {
+        let mut builder = Badge::builder().label(&self.label).message(&self.message);
+        if let Some(colour) = self.colour {
+            builder = builder.colour(colour);
+        }
+        if let Some(label_colour) = self.label_colour {
+            builder = builder.label_colour(label_colour);
+        }
+        if let Some(logo) = &self.logo {
+            builder = builder.logo(logo);
+        }
+        if let Some(logo_width) = self.logo_width {
+            builder = builder.logo_width(logo_width);
+        }
+        // as on shields.io, a single link covers the whole badge
+        match self.links.as_slice() {
+            [] => {}
+            [link] => builder = builder.label_link(link).message_link(link),
+            [label_link, message_link, ..] => {
+                builder = builder.label_link(label_link).message_link(message_link);
+            }
+        }
+        let badge = builder.build();
+        let (content_type, body) = match format {
+            Format::Svg => ("image/svg+xml", badge.render().into_owned().into_bytes()),
+            Format::Json => {
+                let json = serde_json::json!({
+                    "label": self.label,
+                    "message": self.message,
+                    "color": self.colour.map(|c| c.to_string()),
+                    "labelColor": self.label_colour.map(|c| c.to_string()),
+                });
+                ("application/json", json.to_string().into_bytes())
+            }
+            Format::Png => match badge.render_png(1.0) {
+                Ok(png) => ("image/png", png),
+                Err(e) => return Reply::text(500, &e.to_string()),
+            },
+        };
+        Reply {
+            status,
+            content_type,
+            body,
+            cache_seconds,
+        }
+    }
+}
+
+impl Reply {
+    fn text(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: message.as_bytes().to_vec(),
+            cache_seconds: 0,
+        }
+    }
+}
+
+fn header(name: &str, value: &str) -> Option<Header> {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).ok()
+}
+
+/// Whether `a` and `b` are equal, taking the same time for inputs of the same length wherever
+/// they differ, so that a token cannot be guessed byte by byte from response times.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Strip the brackets around an IPv6 address in a URL.
+fn unbracket(host: &str) -> &str {
+    host.strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+        .unwrap_or(host)
+}
+
+/// Clamp a logo width to [`MAX_LOGO_WIDTH`], or `None` if it is negative or not finite.
+fn clamp_logo_width(logo_width: f32) -> Option<f32> {
+    (logo_width.is_finite() && logo_width >= 0.0).then(|| logo_width.min(MAX_LOGO_WIDTH))
+}
+
+/// Whether `logo` is a `data:` URI or an `http` or `https` URL.
+fn is_valid_logo(logo: &str) -> bool {
+    ["data:", "http://", "https://"].iter().any(|scheme| {
+        logo.get(..scheme.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme))
+    })
+}
+
+/// Split the format extension off the end of a path, defaulting to SVG.
+fn split_format(path: &str) -> (&str, Format) {
+    [
+        (".svg", Format::Svg),
+        (".json", Format::Json),
+        (".png", Format::Png),
+    ]
+    .into_iter()
+    .find_map(|(ext, format)| path.strip_suffix(ext).map(|path| (path, format)))
+    .unwrap_or((path, Format::Svg))
+}
+
+/// Parse a shields.io colour name or a hex code, with or without the leading `#`.
+fn parse_colour(s: &str) -> Option<Colour> {
+    Colour::from_shields_name(s).or_else(|| {
+        let hex = s.strip_prefix('#').unwrap_or(s);
+        Colour::from_hex(&format!("#{hex}"))
+    })
+}
+
+/// Parse the `<label>-<message>-<colour>` or `<message>-<colour>` part of a static badge URL.
+fn parse_static(content: &str) -> Option<Params> {
+    let mut parts = vec![String::new()];
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        let part = parts.last_mut()?;
+        match (c, chars.peek()) {
+            ('-', Some('-')) => {
+                chars.next();
+                part.push('-');
+            }
+            ('-', _) => parts.push(String::new()),
+            ('_', Some('_')) => {
+                chars.next();
+                part.push('_');
+            }
+            ('_', _) => part.push(' '),
+            (c, _) => part.push(c),
+        }
+    }
+    if parts.len() == 2 {
+        // a badge without a label
+        parts.insert(0, String::new());
+    }
+    let [label, message, colour] = <[String; 3]>::try_from(parts).ok()?;
+    Some(Params {
+        label,
+        message,
+        colour: parse_colour(&colour),
+        ..Params::default()
+    })
+}
+
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .filter_map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let decode = |s: &str| percent_decode(&s.replace('+', " "));
+            Some((decode(key)?, decode(value)?))
+        })
+        .collect()
+}
+
+/// Decode `%XX` escapes, returning `None` if they are malformed or not UTF-8.
+fn percent_decode(s: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut iter = s.bytes();
+    while let Some(b) = iter.next() {
+        if b == b'%' {
+            let hex = [iter.next()?, iter.next()?];
+            bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            bytes.push(b);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+fn percent_encode(s: &str) -> String {
+    use std::fmt::Write;
+    let mut encoded = String::with_capacity(s.len());
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || b"-_.~".contains(&b) {
+            encoded.push(char::from(b));
+        } else {
+            let _ = write!(encoded, "%{b:02X}");
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_static() {
+        let params = parse_static("build-passing-brightgreen").expect("failed to parse");
+        assert_eq!(params.label, "build");
+        assert_eq!(params.message, "passing");
+        assert_eq!(params.colour, Colour::from_shields_name("brightgreen"));
+
+        let params = parse_static("just--a__test_here-ff0000").expect("failed to parse");
+        assert_eq!(params.label, "");
+        assert_eq!(params.message, "just-a_test here");
+        assert_eq!(params.colour, Some(Colour::from_colour_code(0xff_00_00)));
+
+        assert!(parse_static("too-many-dashes-here").is_none());
+        assert!(parse_static("nodash").is_none());
+    }
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(
+            parse_query("label=hello+world&color=%23ff0000&empty"),
+            [
+                ("label".to_owned(), "hello world".to_owned()),
+                ("color".to_owned(), "#ff0000".to_owned()),
+                ("empty".to_owned(), String::new()),
+            ]
+        );
+        assert!(percent_decode("%zz").is_none());
+        assert_eq!(
+            percent_decode(&percent_encode("<svg a='b'/>")).as_deref(),
+            Some("<svg a='b'/>")
+        );
+    }
+
+    #[test]
+    fn test_allowed_hosts() {
+        let config = ServerConfig::new()
+            .allow_host("example.com")
+            .allow_host("localhost:8000");
+        assert!(config.is_allowed("https://example.com/badge.json"));
+        assert!(config.is_allowed("http://EXAMPLE.com:8080/badge.json"));
+        assert!(config.is_allowed("http://localhost:8000/badge.json"));
+        assert!(!config.is_allowed("http://localhost:8001/badge.json"));
+        assert!(!config.is_allowed("http://localhost/badge.json"));
+        assert!(!config.is_allowed("https://example.org/badge.json"));
+        assert!(!config.is_allowed("file://example.com/etc/passwd"));
+        assert!(!ServerConfig::new().is_allowed("https://example.com/badge.json"));
+
+        let config = ServerConfig::new()
+            .allow_host("::1")
+            .allow_host("[fd00::1]:8000");
+        assert!(config.is_allowed("http://[::1]/badge.json"));
+        assert!(config.is_allowed("http://[0:0::1]:9000/badge.json"));
+        assert!(config.is_allowed("http://[fd00::1]:8000/badge.json"));
+        assert!(!config.is_allowed("http://[fd00::1]:8001/badge.json"));
+        assert!(!config.is_allowed("http://[fd00::2]:8000/badge.json"));
+    }
+
+    #[test]
+    fn test_logo_query() {
+        let mut params = Params::default();
+        assert!(params
+            .apply_query(&parse_query("logo=data%3Aimage%2Fpng%3Bbase64%2CAAAA"))
+            .is_ok());
+        assert!(params
+            .apply_query(&parse_query("logo=HTTPS%3A%2F%2Fexample.com%2Flogo.svg"))
+            .is_ok());
+        for logo in [
+            "%2Fetc%2Fpasswd",
+            "%2Fdev%2Fzero",
+            "logo.svg",
+            "file%3A%2F%2F%2Fetc%2Fpasswd",
+        ] {
+            assert!(params
+                .apply_query(&parse_query(&format!("logo={logo}")))
+                .is_err());
+        }
+        assert_eq!(params.logo.as_deref(), Some("HTTPS://example.com/logo.svg"));
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq("secret", "secret"));
+        assert!(constant_time_eq("", ""));
+        assert!(!constant_time_eq("secret", "secreT"));
+        assert!(!constant_time_eq("secret", "secrets"));
+        assert!(!constant_time_eq("", "secret"));
+    }
+
+    #[test]
+    fn test_logo_width_query() {
+        let mut params = Params::default();
+        assert!(params
+            .apply_query(&parse_query("logoWidth=2000000"))
+            .is_ok());
+        assert_eq!(params.logo_width, Some(MAX_LOGO_WIDTH));
+        assert!(params.apply_query(&parse_query("logoWidth=20")).is_ok());
+        assert_eq!(params.logo_width, Some(20.0));
+        for logo_width in ["-1", "NaN", "inf", "wide"] {
+            assert!(params
+                .apply_query(&parse_query(&format!("logoWidth={logo_width}")))
+                .is_err());
+        }
+        let badge = EndpointBadge::new("a", "b").logo_width(f32::INFINITY);
+        assert_eq!(Params::from(badge).logo_width, None);
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use crate::{colour::Colour, geometry::Geometry, layout::Rect, xml::Writer};
+
+/// A tiny line chart drawn in a [`Segment`](crate::Segment), such as of benchmark timings or
+/// download counts over time.
+///
+/// The chart is drawn after the segment's text, which can be left empty for a chart on its own.
+/// Values are scaled to fill the height of the chart, from the smallest at the bottom to the
+/// largest at the top, and values that are not finite numbers are left out.
+///
+/// # Example
+///
+/// ```rust
+/// use badges::{Badge, Render, Sparkline};
+/// let badge = Badge::builder()
+///     .label("bench")
+///     .message("12ms")
+///     .sparkline(Sparkline::new([14.0, 13.2, 13.5, 12.1, 12.0]).area())
+///     .build();
+/// assert!(badge.render().contains("<path"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sparkline {
+    values: Vec<f64>,
+    width: Option<f32>,
+    area: bool,
+    colour: Option<Colour>,
+}
+
+impl Sparkline {
+    /// Create a sparkline of `values`, from oldest to newest.
+    #[must_use]
+    pub fn new(values: impl IntoIterator<Item = f64>) -> Self {
+        Self {
+            values: values.into_iter().filter(|v| v.is_finite()).collect(),
+            width: None,
+            area: false,
+            colour: None,
+        }
+    }
+
+    /// Set the width of the chart.
+    ///
+    /// Defaults to three times the [logo size](Geometry::logo_size), which is 42 pixels for
+    /// shields.io badges.
+    #[must_use]
+    pub const fn width(mut self, width: f32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Shade the area under the line.
+    #[must_use]
+    pub const fn area(mut self) -> Self {
+        self.area = true;
+        self
+    }
+
+    /// Set the colour of the line and the area under it.
+    ///
+    /// Defaults to the colour of the segment's text, which contrasts with its background.
+    #[must_use]
+    pub const fn colour(mut self, colour: Colour) -> Self {
+        self.colour = Some(colour);
+        self
+    }
+
+    /// The newest value, if there are any.
+    pub(crate) fn latest(&self) -> Option<f64> {
+        self.values.last().copied()
+    }
+
+    /// The width the chart takes up in a badge with `geometry`, which is zero without values.
+    pub(crate) fn width_or_default(&self, geometry: &Geometry) -> f32 {
+        if self.values.is_empty() {
+            return 0.0;
+        }
+        self.width.unwrap_or(3.0 * geometry.logo_size)
+    }
+
+    /// The points of the chart within `rect`, from left to right.
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    fn points(&self, rect: Rect) -> Vec<(f32, f32)> {
+        let (min, max) = self
+            .values
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &v| {
+                (min.min(v), max.max(v))
+            });
+        let bottom = rect.y() + rect.height();
+        let y = |v: f64| {
+            let height = if max > min {
+                ((v - min) / (max - min)) as f32
+            } else {
+                0.5
+            };
+            rect.height().mul_add(-height, bottom)
+        };
+        if let [value] = self.values[..] {
+            // a single value is drawn as a flat line across the chart
+            return vec![(rect.x(), y(value)), (rect.x() + rect.width(), y(value))];
+        }
+        let step = rect.width() / (self.values.len() - 1) as f32;
+        self.values
+            .iter()
+            .enumerate()
+            .map(|(index, &v)| (step.mul_add(index as f32, rect.x()), y(v)))
+            .collect()
+    }
+
+    /// Write the `<path>` elements of the chart within `rect`, drawn in `colour` unless the
+    /// sparkline sets its own.
+    pub(crate) fn write(&self, w: &mut Writer, rect: Rect, colour: Colour) -> fmt::Result {
+        if self.values.is_empty() {
+            return Ok(());
+        }
+        let colour = self.colour.unwrap_or(colour);
+        let points = self.points(rect);
+        if self.area {
+            w.element("path")
+                .attr_display(
+                    "d",
+                    &PathData {
+                        points: &points,
+                        area: Some(rect),
+                    },
+                )
+                .attr("fill", colour)
+                .attr("fill-opacity", ".3")
+                .empty()?;
+        }
+        w.element("path")
+            .attr_display(
+                "d",
+                &PathData {
+                    points: &points,
+                    area: None,
+                },
+            )
+            .attr("fill", "none")
+            .attr("stroke", colour)
+            .attr("stroke-width", "1")
+            .attr("stroke-linecap", "round")
+            .attr("stroke-linejoin", "round")
+            .empty()
+    }
+}
+
+/// The data of a `<path>` through `points`, closed along the bottom of the `area` under it if
+/// there is one.
+struct PathData<'p> {
+    points: &'p [(f32, f32)],
+    area: Option<Rect>,
+}
+
+impl fmt::Display for PathData<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (x, y)) in self.points.iter().enumerate() {
+            let command = if index == 0 { 'M' } else { 'L' };
+            write!(f, "{command}{x:.1} {y:.1}")?;
+        }
+        if let Some(rect) = self.area {
+            write!(
+                f,
+                "L{right:.1} {bottom:.1}L{left:.1} {bottom:.1}Z",
+                right = rect.x() + rect.width(),
+                bottom = rect.y() + rect.height(),
+                left = rect.x(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Hash for Sparkline {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.values.len().hash(state);
+        for value in &self.values {
+            value.to_bits().hash(state);
+        }
+        self.width.map(f32::to_bits).hash(state);
+        self.area.hash(state);
+        self.colour.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Badge, Render};
+
+    #[test]
+    fn test_points() {
+        let sparkline = Sparkline::new([1.0, f64::NAN, 3.0, 2.0, 5.0]).width(30.0);
+        let badge = Badge::builder().sparkline(sparkline.clone()).build();
+        let rect = badge.layout().segments()[0]
+            .chart()
+            .expect("segment has no chart");
+        assert_eq!(
+            sparkline.points(rect),
+            [(5.0, 17.0), (15.0, 10.0), (25.0, 13.5), (35.0, 3.0)]
+        );
+        let flat = Sparkline::new([4.0]);
+        assert_eq!(flat.points(rect), [(5.0, 10.0), (35.0, 10.0)]);
+    }
+
+    #[test]
+    fn test_paths() {
+        let badge = Badge::builder()
+            .message("5")
+            .sparkline(Sparkline::new([1.0, 5.0]).width(20.0).area())
+            .build();
+        let svg = badge.render();
+        assert_eq!(svg.matches("<path").count(), 2);
+        assert!(svg.contains(r#"fill-opacity=".3""#));
+
+        let empty = Badge::builder()
+            .message("5")
+            .sparkline(Sparkline::new([]))
+            .build();
+        assert!(!empty.render().contains("<path"));
+        assert!(empty.layout().segments()[0].chart().is_none());
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{badge::Builder, colour::Colour, geometry::Geometry};
+
+/// Defaults shared by many badges, such as their colours, font and geometry.
+///
+/// A theme holds a [`BadgeBuilder`](Builder) with every option it sets, and
+/// [`Theme::builder`] hands out copies of it to finish off with a label and message. Options
+/// set on the returned builder override the theme's.
+///
+/// # Example
+///
+/// ```rust
+/// use badges::{Badge, Colour, Geometry, Render, Theme};
+/// let brand = Theme::new(
+///     Badge::builder()
+///         .label_colour(Colour::from_colour_code(0x1e_3a_5f))
+///         .font_family("Inter,sans-serif")
+///         .geometry(Geometry::new().corner_radius(0.0)),
+/// );
+/// let build = brand.builder().label("build").message("passing").build();
+/// let coverage = brand.builder().label("coverage").message("91%").build();
+/// assert!(build.render().contains(r##"fill="#1e3a5f""##));
+/// assert!(coverage.render().contains(r#"font-family="Inter,sans-serif""#));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Theme<'a> {
+    defaults: Builder<'a>,
+}
+
+impl<'a> Theme<'a> {
+    /// Create a theme from a builder with the defaults set on it.
+    #[must_use]
+    pub const fn new(defaults: Builder<'a>) -> Self {
+        Self { defaults }
+    }
+
+    /// The default style of shields.io badges, which is that of
+    /// [`Badge::builder`](crate::Badge::builder).
+    #[must_use]
+    pub fn shields() -> Self {
+        Self::default()
+    }
+
+    /// The `flat-square` style of shields.io badges, with square corners and neither a gradient
+    /// nor a text shadow.
+    #[must_use]
+    pub fn flat_square() -> Self {
+        Self::new(
+            Builder::default()
+                .geometry(Geometry::new().corner_radius(0.0))
+                .gradient(false)
+                .shadow(false),
+        )
+    }
+
+    /// A flat style for pages with dark backgrounds, with a dark label and a muted green
+    /// message.
+    #[must_use]
+    pub fn dark() -> Self {
+        Self::new(
+            Builder::default()
+                .label_colour(Colour::from_colour_code(0x30_36_3d))
+                .colour(Colour::from_colour_code(0x23_86_36))
+                .gradient(false)
+                .shadow(false),
+        )
+    }
+
+    /// A [`BadgeBuilder`](Builder) with the theme's defaults.
+    #[must_use]
+    pub fn builder(&self) -> Builder<'a> {
+        self.defaults.clone()
+    }
+}
+
+impl<'a> From<Builder<'a>> for Theme<'a> {
+    fn from(defaults: Builder<'a>) -> Self {
+        Self::new(defaults)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Badge, Render};
+
+    #[test]
+    fn test_presets() {
+        let render = |theme: Theme| {
+            theme
+                .builder()
+                .label("build")
+                .message("passing")
+                .build()
+                .render()
+                .into_owned()
+        };
+        let plain = Badge::builder()
+            .label("build")
+            .message("passing")
+            .build()
+            .render();
+        assert_eq!(render(Theme::shields()), plain);
+
+        let square = render(Theme::flat_square());
+        assert!(square.contains(r#"rx="0.0""#));
+        assert!(!square.contains("linearGradient") && !square.contains("aria-hidden"));
+
+        let dark = render(Theme::dark());
+        assert!(dark.contains(r##"fill="#30363d""##) && dark.contains(r##"fill="#238636""##));
+    }
+
+    #[test]
+    fn test_overrides() {
+        let theme = Theme::from(Badge::builder().colour(Colour::RED).label("status"));
+        let badge = theme.builder().message("ok").colour(Colour::GREEN).build();
+        let svg = badge.render();
+        assert!(svg.contains("<title>status: ok</title>"));
+        assert!(svg.contains(r##"fill="#008000""##) && !svg.contains(r##"fill="#ff0000""##));
+    }
+}
@@ -1,9 +1,87 @@
-use std::{borrow::Cow, fmt::Write};
+use std::{
+    borrow::Cow,
+    fmt::{self, Write},
+    io,
+};
 
 /// A trait that indicates that a type can be rendered as XML.
 pub trait Render<'a> {
     /// Render the type as an XML string.
     fn render(&self) -> Cow<'a, str>;
+
+    /// Render the type as XML, writing it to `w`.
+    ///
+    /// The types in this crate stream their output into the writer as it is produced, without
+    /// building up intermediate strings, so this is the most efficient way to render into an
+    /// existing buffer. The default implementation writes the output of [`Render::render`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `w` fails.
+    fn render_to(&self, w: &mut dyn Write) -> fmt::Result {
+        w.write_str(&self.render())
+    }
+
+    /// Render the type as XML, writing it to the I/O stream `w`.
+    ///
+    /// The output is written in many small pieces, so unbuffered streams like files and sockets
+    /// should be wrapped in a [`std::io::BufWriter`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `w` fails.
+    fn render_to_io(&self, w: &mut dyn io::Write) -> io::Result<()> {
+        let mut adaptor = IoAdaptor {
+            inner: w,
+            error: None,
+        };
+        match self.render_to(&mut adaptor) {
+            Ok(()) => Ok(()),
+            Err(fmt::Error) => Err(adaptor
+                .error
+                .unwrap_or_else(|| io::Error::other("formatter error"))),
+        }
+    }
+
+    /// Render the type as a PNG image, scaled by `scale`.
+    ///
+    /// A scale of `1.0` produces an image the same size as the SVG, while higher values are
+    /// useful for high-DPI displays. The output is deterministic, as text is rendered with a
+    /// bundled font rather than whatever fonts are installed on the system.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `scale` is not a positive number, or if the SVG cannot be parsed,
+    /// which usually indicates an invalid logo.
+    #[cfg(feature = "png")]
+    fn render_png(&self, scale: f32) -> Result<Vec<u8>, crate::PngError> {
+        crate::png::rasterize(&self.render(), scale)
+    }
+}
+
+/// Render `r` into a new string through [`Render::render_to`], for types that stream their
+/// output.
+pub fn render_string<'a>(r: &(impl Render<'a> + ?Sized)) -> Cow<'a, str> {
+    let mut s = String::new();
+    r.render_to(&mut s)
+        .expect("Writing to a String cannot fail");
+    Cow::Owned(s)
+}
+
+/// Adapts an [`io::Write`] into a [`fmt::Write`], keeping hold of the underlying I/O error,
+/// which [`fmt::Error`] cannot carry.
+struct IoAdaptor<'w, W: ?Sized> {
+    inner: &'w mut W,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write + ?Sized> Write for IoAdaptor<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
 }
 
 pub trait IteratorExt: Iterator {
@@ -25,3 +103,34 @@ pub trait IteratorExt: Iterator {
 }
 
 impl<T: Iterator> IteratorExt for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Badge;
+
+    /// An implementor that only provides `render`, like those written before `render_to`.
+    struct Prerendered;
+
+    impl Render<'static> for Prerendered {
+        fn render(&self) -> Cow<'static, str> {
+            Cow::Borrowed("<svg/>")
+        }
+    }
+
+    #[test]
+    fn test_dyn_render() {
+        let badge = Badge::builder().message("passing").build();
+        let renderables: [&dyn Render<'static>; 2] = [&Prerendered, &badge];
+        for renderable in renderables {
+            let mut s = String::new();
+            renderable.render_to(&mut s).expect("failed to render");
+            assert_eq!(s, renderable.render());
+            let mut bytes = Vec::new();
+            renderable
+                .render_to_io(&mut bytes)
+                .expect("failed to render");
+            assert_eq!(bytes, s.as_bytes());
+        }
+    }
+}
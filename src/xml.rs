@@ -1,134 +1,240 @@
-use crate::traits::{IteratorExt, Render};
-use std::borrow::Cow;
+use crate::colour::Colour;
+use std::fmt::{self, Write};
 
-#[derive(Debug, Clone)]
-pub struct Attribute<'a> {
-    name: &'a str,
-    value: Cow<'a, str>,
+/// The value of an attribute.
+///
+/// Values are kept in their original form and only formatted as the attribute is written, so
+/// that rendering can write straight into the output without intermediate strings.
+#[derive(Clone, Copy)]
+pub enum Value<'a> {
+    Str(&'a str),
+    Float(f32),
+    Colour(Colour),
+    /// A reference to the element with the given id, i.e. `url(#id)`.
+    Url(&'a str),
+    /// Anything else that can be formatted, which is escaped as it is written.
+    Display(&'a dyn fmt::Display),
 }
 
-impl std::fmt::Display for Attribute<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}={:?}", self.name, self.value)
+impl<'a> From<&'a str> for Value<'a> {
+    fn from(value: &'a str) -> Self {
+        Self::Str(value)
     }
 }
 
-fn escape_xml(s: &str) -> Cow<str> {
-    let raw = s.as_bytes();
-    let mut escaped = None;
-    let mut iterator = raw.iter();
-    let mut pos = 0;
-    while let Some(i) = iterator.position(|b| matches!(*b, b'<' | b'>' | b'&' | b'"' | b'\'')) {
-        if escaped.is_none() {
-            escaped = Some(Vec::with_capacity(s.len()));
-        }
-        let escaped = escaped.as_mut().expect("Uninitialized vector");
-        let new_pos = pos + i;
-        escaped.extend_from_slice(&raw[pos..new_pos]);
-        match raw[new_pos] {
-            b'<' => escaped.extend_from_slice(b"&lt;"),
-            b'>' => escaped.extend_from_slice(b"&gt;"),
-            b'&' => escaped.extend_from_slice(b"&amp;"),
-            b'"' => escaped.extend_from_slice(b"&quot;"),
-            b'\'' => escaped.extend_from_slice(b"&apos;"),
-            _ => unreachable!("Only <, >, &, \" and 'are escaped"),
-        }
-        pos = new_pos + 1;
+impl<'a> From<&'a String> for Value<'a> {
+    fn from(value: &'a String) -> Self {
+        Self::Str(value)
     }
-    escaped.map_or(Cow::Borrowed(s), |mut escaped| {
-        if let Some(raw) = raw.get(pos..) {
-            escaped.extend_from_slice(raw);
-        }
-        Cow::Owned(
-            String::from_utf8(escaped).expect("Invalid UTF-8 inside a &str, should be impossible."),
-        )
-    })
 }
 
-#[derive(Debug, Clone)]
-pub enum Content<'a> {
-    Text(&'a str),
-    Element(Element<'a>),
-    List(ElementList<'a>),
+impl From<Colour> for Value<'_> {
+    fn from(value: Colour) -> Self {
+        Self::Colour(value)
+    }
 }
 
-impl Default for Content<'_> {
-    fn default() -> Self {
-        Self::Text("")
+impl Value<'_> {
+    fn write_to(self, w: &mut dyn Write) -> fmt::Result {
+        match self {
+            Self::Str(s) => write_escaped(w, s),
+            Self::Float(f) => w.write_str(ryu::Buffer::new().format_finite(f)),
+            Self::Colour(c) => write!(w, "{c}"),
+            Self::Url(id) => {
+                w.write_str("url(#")?;
+                write_escaped(w, id)?;
+                w.write_char(')')
+            }
+            Self::Display(d) => write!(Escaped(w), "{d}"),
+        }
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct Element<'a> {
-    name: &'a str,
-    content: Vec<Content<'a>>,
-    attrs: Vec<Attribute<'a>>,
+/// Write `s` to `w`, escaping the characters that have a special meaning in XML.
+fn write_escaped(w: &mut dyn Write, s: &str) -> fmt::Result {
+    let mut pos = 0;
+    for (i, b) in s.bytes().enumerate() {
+        let escaped = match b {
+            b'<' => "&lt;",
+            b'>' => "&gt;",
+            b'&' => "&amp;",
+            b'"' => "&quot;",
+            b'\'' => "&apos;",
+            _ => continue,
+        };
+        w.write_str(&s[pos..i])?;
+        w.write_str(escaped)?;
+        pos = i + 1;
+    }
+    w.write_str(&s[pos..])
 }
 
-impl<'a> Element<'a> {
-    pub fn new(name: &'a str) -> Self {
-        Self {
-            name,
-            content: Vec::default(),
-            attrs: Vec::default(),
-        }
+/// A writer escaping everything written through it.
+struct Escaped<'w>(&'w mut dyn Write);
+
+impl Write for Escaped<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        write_escaped(self.0, s)
     }
+}
 
-    pub fn content(mut self, content: Vec<Content<'a>>) -> Self {
-        self.content = content;
-        self
+/// Writes XML into a [`fmt::Write`] as it is produced, without building up a tree of elements.
+pub struct Writer<'w> {
+    w: &'w mut dyn Write,
+}
+
+impl<'w> Writer<'w> {
+    pub fn new(w: &'w mut dyn Write) -> Self {
+        Self { w }
     }
 
-    pub fn attr(mut self, name: &'a str, value: impl Into<Cow<'a, str>>) -> Self {
-        self.attrs.push(Attribute {
+    /// Start the element called `name`, whose attributes are added to the returned [`Tag`].
+    pub fn element<'t>(&'t mut self, name: &'static str) -> Tag<'t, 'w> {
+        let result = self.w.write_char('<').and_then(|()| self.w.write_str(name));
+        Tag {
+            writer: self,
             name,
-            value: value.into(),
-        });
-        self
+            result,
+        }
     }
 
-    pub fn attr_float(self, name: &'a str, value: f32) -> Self {
-        let mut buf = ryu::Buffer::new();
-        self.attr(name, buf.format_finite(value).to_string())
+    /// Write `s` as text, escaping it.
+    pub fn text(&mut self, s: &str) -> fmt::Result {
+        write_escaped(self.w, s)
     }
-}
 
-impl<'a> Render<'a> for Element<'a> {
-    fn render(&self) -> Cow<'a, str> {
-        let attrs_str = self.attrs.iter().map(ToString::to_string).join(" ");
-        match self.content.iter().peekable().peek() {
-            None => format!("<{} {}/>", self.name, attrs_str).into(),
-            Some(_) => {
-                let content = self.content.iter().map(Render::render).join("");
-                format!("<{0} {1}>{2}</{0}>", self.name, attrs_str, content).into()
-            }
+    /// Write `s` as-is inside a CDATA section, such as the CSS of a `<style>` element.
+    pub fn cdata(&mut self, s: &str) -> fmt::Result {
+        self.w.write_str("<![CDATA[")?;
+        // a CDATA section cannot contain its terminator, so split it across two sections
+        let mut parts = s.split("]]>");
+        if let Some(first) = parts.next() {
+            self.w.write_str(first)?;
+        }
+        for part in parts {
+            self.w.write_str("]]]]><![CDATA[>")?;
+            self.w.write_str(part)?;
         }
+        self.w.write_str("]]>")
     }
 }
 
-impl<'a> Render<'a> for Content<'a> {
-    fn render(&self) -> Cow<'a, str> {
-        match self {
-            Self::Text(s) => escape_xml(s),
-            Self::Element(e) => e.render(),
-            Self::List(l) => l.render(),
+/// The start tag of an element, which is written as attributes are added to it.
+///
+/// The element is finished with [`Tag::empty`] or [`Tag::content`], which also return the first
+/// error from writing its attributes.
+#[must_use]
+pub struct Tag<'t, 'w> {
+    writer: &'t mut Writer<'w>,
+    name: &'static str,
+    result: fmt::Result,
+}
+
+impl<'w> Tag<'_, 'w> {
+    pub fn attr<'v>(mut self, name: &str, value: impl Into<Value<'v>>) -> Self {
+        if self.result.is_ok() {
+            let w = &mut *self.writer.w;
+            self.result = w
+                .write_char(' ')
+                .and_then(|()| w.write_str(name))
+                .and_then(|()| w.write_str("=\""))
+                .and_then(|()| value.into().write_to(w))
+                .and_then(|()| w.write_char('"'));
         }
+        self
     }
-}
 
-#[derive(Debug, Clone)]
-pub struct ElementList<'a> {
-    content: Vec<Content<'a>>,
-}
+    pub fn attr_float(self, name: &str, value: f32) -> Self {
+        self.attr(name, Value::Float(value))
+    }
 
-impl<'a> ElementList<'a> {
-    pub fn new(content: Vec<Content<'a>>) -> Self {
-        Self { content }
+    pub fn attr_url(self, name: &str, id: &str) -> Self {
+        self.attr(name, Value::Url(id))
+    }
+
+    pub fn attr_display(self, name: &str, value: &dyn fmt::Display) -> Self {
+        self.attr(name, Value::Display(value))
+    }
+
+    /// Finish the element without any content.
+    pub fn empty(self) -> fmt::Result {
+        self.result?;
+        self.writer.w.write_str("/>")
+    }
+
+    /// Finish the element with the content written by `f`.
+    pub fn content(self, f: impl FnOnce(&mut Writer<'w>) -> fmt::Result) -> fmt::Result {
+        self.result?;
+        self.writer.w.write_char('>')?;
+        f(self.writer)?;
+        self.writer.w.write_str("</")?;
+        self.writer.w.write_str(self.name)?;
+        self.writer.w.write_char('>')
+    }
+
+    /// Finish the element with `text` as its content.
+    pub fn text(self, text: &str) -> fmt::Result {
+        self.content(|w| w.text(text))
     }
 }
 
-impl<'a> Render<'a> for ElementList<'a> {
-    fn render(&self) -> Cow<'a, str> {
-        self.content.iter().map(Render::render).join("").into()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(f: impl FnOnce(&mut Writer) -> fmt::Result) -> String {
+        let mut s = String::new();
+        f(&mut Writer::new(&mut s)).expect("failed to render");
+        s
+    }
+
+    #[test]
+    fn test_escaping() {
+        let xml = render(|w| {
+            w.element("text")
+                .attr("title", "\"quoted\"")
+                .attr_url("fill", "a&b")
+                .attr_display("class", &format_args!("{}", "<c>"))
+                .text("<a & 'b'>")
+        });
+        assert_eq!(
+            xml,
+            r#"<text title="&quot;quoted&quot;" fill="url(#a&amp;b)" class="&lt;c&gt;">&lt;a &amp; &apos;b&apos;&gt;</text>"#
+        );
+    }
+
+    #[test]
+    fn test_cdata() {
+        let xml = render(|w| {
+            w.element("style")
+                .content(|w| w.cdata("a > b { content: \"]]>\" }"))
+        });
+        assert_eq!(
+            xml,
+            r#"<style><![CDATA[a > b { content: "]]]]><![CDATA[>" }]]></style>"#
+        );
+    }
+
+    #[test]
+    fn test_empty_element() {
+        let xml = render(|w| {
+            w.element("rect")
+                .attr_float("width", 20.0)
+                .attr("fill", Colour::from_colour_code(0x44_cc_11))
+                .empty()
+        });
+        assert_eq!(xml, r##"<rect width="20.0" fill="#44cc11"/>"##);
+        assert_eq!(render(|w| w.element("g").empty()), "<g/>");
+    }
+
+    #[test]
+    fn test_nested() {
+        let xml = render(|w| {
+            w.element("g").attr("fill", "#fff").content(|w| {
+                w.element("title").text("a")?;
+                w.element("g").content(|w| w.text("b"))
+            })
+        });
+        assert_eq!(xml, r##"<g fill="#fff"><title>a</title><g>b</g></g>"##);
     }
 }
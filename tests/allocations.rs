@@ -0,0 +1,93 @@
+//! Regression tests for the number of heap allocations made when rendering a badge.
+//!
+//! The bounds are deliberately a little above the current counts, so that small refactors do
+//! not need to touch these tests but accidental per-character, per-element or quadratic allocation
+//! does.
+
+mod common;
+
+use std::io;
+
+use badges::{Badge, Colour, Render, Segment};
+use common::allocations;
+
+fn assert_allocations_at_most(badge: &Badge, max: usize) {
+    let mut buf = String::with_capacity(8192);
+    let render_to = allocations(|| badge.render_to(&mut buf).expect("failed to render"));
+    assert!(
+        render_to <= max,
+        "render_to made {render_to} allocations, expected at most {max}"
+    );
+    let render_to_io = allocations(|| {
+        badge
+            .render_to_io(&mut io::sink())
+            .expect("failed to render");
+    });
+    assert!(
+        render_to_io <= max,
+        "render_to_io made {render_to_io} allocations, expected at most {max}"
+    );
+    // `render` additionally has to grow the returned string
+    let render = allocations(|| drop(badge.render()));
+    assert!(
+        render <= max + 12,
+        "render made {render} allocations, expected at most {}",
+        max + 12
+    );
+}
+
+#[test]
+fn test_typical() {
+    let badge = Badge::builder().label("build").message("passing").build();
+    assert_allocations_at_most(&badge, 12);
+}
+
+#[test]
+fn test_long_text() {
+    // allocations must not scale with the length of the text
+    let label = "a".repeat(1000);
+    let message = "b".repeat(1000);
+    let badge = Badge::builder().label(&label).message(&message).build();
+    assert_allocations_at_most(&badge, 14);
+}
+
+#[test]
+fn test_unicode() {
+    let badge = Badge::builder()
+        .label("ビルド状態")
+        .message("成功 ✓ — Ünïcødé ✨🚀")
+        .build();
+    assert_allocations_at_most(&badge, 13);
+}
+
+#[test]
+fn test_logo() {
+    let badge = Badge::builder()
+        .label("rust")
+        .message("1.95")
+        .logo("data:image/svg+xml;base64,PHN2Zy8+")
+        .build();
+    assert_allocations_at_most(&badge, 12);
+}
+
+#[test]
+fn test_segments() {
+    let badge = Badge::builder()
+        .label("coverage")
+        .message("91%")
+        .segment(Segment::new("+2.1%").colour(Colour::SEA_GREEN))
+        .message_link("https://example.com/coverage")
+        .build();
+    assert_allocations_at_most(&badge, 15);
+}
+
+#[test]
+fn test_many_segments() {
+    // allocations must not scale with the number of elements either
+    let mut builder = Badge::builder().label("stages").message("1");
+    for stage in 2..=20 {
+        builder = builder.segment(Segment::new(stage.to_string()).colour(Colour::SEA_GREEN));
+    }
+    let badge = builder.build();
+    assert_allocations_at_most(&badge, 15);
+}
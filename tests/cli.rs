@@ -0,0 +1,238 @@
+//! Tests for the `badges` command line tool.
+#![cfg(feature = "cli")]
+
+use std::process::{Command, Output};
+
+fn badges(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_badges"))
+        .args(args)
+        .output()
+        .expect("failed to run badges")
+}
+
+#[test]
+fn test_stdout() {
+    let output = badges(&[
+        "--label",
+        "build",
+        "--message",
+        "passing",
+        "--colour",
+        "#4c1",
+        "--label-colour",
+        "333",
+    ]);
+    assert!(output.status.success());
+    let svg = String::from_utf8(output.stdout).expect("output is not UTF-8");
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.contains("<title>build: passing</title>"));
+    assert!(svg.contains(r##"fill="#44cc11""##));
+    assert!(svg.contains(r##"fill="#333333""##));
+}
+
+#[test]
+fn test_output_file() {
+    let path = std::env::temp_dir().join(format!("badges-cli-{}.svg", std::process::id()));
+    let path_str = path.to_str().expect("temp path is not UTF-8");
+    let output = badges(&[
+        "-m",
+        "passing",
+        "--segment",
+        "2m 31s:#708090",
+        "-o",
+        path_str,
+    ]);
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    let svg = std::fs::read_to_string(&path).expect("badge was not written");
+    std::fs::remove_file(&path).expect("failed to remove badge");
+    assert!(svg.contains("<title>passing: 2m 31s</title>"));
+    assert!(svg.contains(r##"fill="#708090""##));
+}
+
+#[test]
+fn test_theme() {
+    for theme in ["flat-square", "dark"] {
+        let output = badges(&["--theme", theme, "-m", "x"]);
+        assert!(output.status.success());
+        let svg = String::from_utf8(output.stdout).expect("output is not UTF-8");
+        assert!(!svg.contains("<linearGradient"), "{theme}: {svg}");
+        assert!(!svg.contains(r#"aria-hidden="true""#), "{theme}: {svg}");
+    }
+    let output = badges(&["-m", "x", "--no-gradient"]);
+    let svg = String::from_utf8(output.stdout).expect("output is not UTF-8");
+    assert!(!svg.contains("<linearGradient"));
+    assert!(svg.contains(r#"aria-hidden="true""#));
+}
+
+#[test]
+fn test_named_colour() {
+    let output = badges(&[
+        "-l",
+        "build",
+        "-m",
+        "x",
+        "-c",
+        "brightgreen",
+        "--label-colour",
+        "Informational",
+    ]);
+    assert!(output.status.success());
+    let svg = String::from_utf8(output.stdout).expect("output is not UTF-8");
+    assert!(svg.contains(r##"fill="#44cc11""##));
+    assert!(svg.contains(r##"fill="#007ec6""##));
+}
+
+#[test]
+fn test_invalid_colour() {
+    for args in [
+        &["-m", "passing", "--colour", "greenish"][..],
+        &["-m", "passing", "--segment", "extra:#nope"][..],
+    ] {
+        let output = badges(args);
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+        let stderr = String::from_utf8(output.stderr).expect("stderr is not UTF-8");
+        assert!(stderr.contains("expected a hex colour"), "{stderr}");
+    }
+}
+
+#[test]
+fn test_invalid_progress() {
+    for progress in ["NaN", "inf", "half"] {
+        let output = badges(&["-m", "x", "--progress", progress]);
+        assert!(!output.status.success(), "{progress}");
+        assert!(output.stdout.is_empty());
+    }
+    assert!(badges(&["-m", "x", "--progress", "0.5"]).status.success());
+}
+
+#[test]
+fn test_invalid_id_prefix() {
+    let output = badges(&["-m", "x", "--id-prefix", "a b"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).expect("stderr is not UTF-8");
+    assert!(stderr.contains("invalid id prefix `a b`"), "{stderr}");
+    assert!(!stderr.contains("panicked"), "{stderr}");
+}
+
+/// A fresh directory for a test to write a manifest and badges into.
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("badges-cli-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("failed to create temp dir");
+    dir
+}
+
+fn batch(dir: &std::path::Path, manifest: &str, contents: &str) -> (bool, String) {
+    let path = dir.join(manifest);
+    std::fs::write(&path, contents).expect("failed to write manifest");
+    let output = badges(&["batch", path.to_str().expect("temp path is not UTF-8")]);
+    let stderr = String::from_utf8(output.stderr).expect("stderr is not UTF-8");
+    (output.status.success(), stderr)
+}
+
+#[test]
+fn test_batch_toml() {
+    let dir = temp_dir("toml");
+    let manifest = r##"
+[[badge]]
+label = "build"
+message = "passing"
+colour = "#4c1"
+output = "out/build.svg"
+
+[[badge]]
+message = "MIT"
+segments = ["2024:#708090"]
+output = "license.svg"
+"##;
+    let (success, stderr) = batch(&dir, "badges.toml", manifest);
+    assert!(success, "{stderr}");
+    assert!(
+        stderr.contains("2 written, 0 unchanged, 0 failed"),
+        "{stderr}"
+    );
+    let svg = std::fs::read_to_string(dir.join("out/build.svg")).expect("badge was not written");
+    assert!(svg.contains("<title>build: passing</title>"));
+    let svg = std::fs::read_to_string(dir.join("license.svg")).expect("badge was not written");
+    assert!(svg.contains("<title>MIT: 2024</title>"));
+
+    // running again leaves the files alone
+    let (success, stderr) = batch(&dir, "badges.toml", manifest);
+    assert!(success, "{stderr}");
+    assert!(
+        stderr.contains("0 written, 2 unchanged, 0 failed"),
+        "{stderr}"
+    );
+    std::fs::remove_dir_all(&dir).expect("failed to remove temp dir");
+}
+
+#[test]
+fn test_batch_errors_have_lines() {
+    let dir = temp_dir("errors");
+    let (success, stderr) = batch(
+        &dir,
+        "badges.json",
+        r#"[
+  {"message": "passing", "output": "ok.svg"},
+  {"message": "failing",
+   "colour": "nope", "output": "bad.svg"},
+  {"message": "unknown"}
+]"#,
+    );
+    assert!(!success);
+    assert!(stderr.contains("badges.json:4: "), "{stderr}");
+    assert!(
+        stderr.contains("badges.json:5: missing `output`"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains("1 written, 0 unchanged, 2 failed"),
+        "{stderr}"
+    );
+    assert!(dir.join("ok.svg").exists());
+
+    let (success, stderr) = batch(
+        &dir,
+        "badges.csv",
+        "label,message,colour,output\nbuild,passing,#4c1,build.svg\nlicense,MIT,#zzz,license.svg\n",
+    );
+    assert!(!success);
+    assert!(
+        stderr.contains("badges.csv:3: expected a hex colour"),
+        "{stderr}"
+    );
+    assert!(dir.join("build.svg").exists());
+    std::fs::remove_dir_all(&dir).expect("failed to remove temp dir");
+}
+
+#[test]
+fn test_batch_csv_lists() {
+    let dir = temp_dir("csv");
+    let manifest = "\
+label,message,segments,cycle,sparkline,logo_width,multi_line,output
+year,2024,42|7:#708090,,,,,year.svg
+bench,12ms,,13ms|14,1.5|2|3,,false,bench.svg
+";
+    let (success, stderr) = batch(&dir, "badges.csv", manifest);
+    assert!(success, "{stderr}");
+    let svg = std::fs::read_to_string(dir.join("year.svg")).expect("badge was not written");
+    assert!(svg.contains("<title>year: 2024, 42, 7</title>"), "{svg}");
+    assert!(svg.contains(r##"fill="#708090""##), "{svg}");
+    let svg = std::fs::read_to_string(dir.join("bench.svg")).expect("badge was not written");
+    assert!(svg.contains(">14</text>"), "{svg}");
+    assert!(svg.contains("<path"), "{svg}");
+
+    let (success, stderr) = batch(
+        &dir,
+        "badges.csv",
+        "message,sparkline,output\nslow,1|x,slow.svg\n",
+    );
+    assert!(!success);
+    assert!(
+        stderr.contains("badges.csv:2: invalid item `x`"),
+        "{stderr}"
+    );
+    std::fs::remove_dir_all(&dir).expect("failed to remove temp dir");
+}
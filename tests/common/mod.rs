@@ -0,0 +1,51 @@
+//! A global allocator counting the heap allocations made on each thread, shared by the
+//! allocation tests and the benchmarks.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+struct CountingAllocator;
+
+thread_local! {
+    // counted per thread, as tests run concurrently
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn count() {
+    // `try_with` so that allocations made while the thread is being torn down don't panic
+    let _ = ALLOCATIONS.try_with(|a| a.set(a.get() + 1));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count();
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count();
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// The number of allocations made by the current thread so far.
+pub fn allocated() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+/// The number of allocations made by the current thread while running `f`.
+#[allow(dead_code)] // unused by the benchmarks
+pub fn allocations(f: impl FnOnce()) -> usize {
+    let before = allocated();
+    f();
+    allocated() - before
+}
@@ -0,0 +1,266 @@
+//! Tests for the badge server, run against a local endpoint.
+#![cfg(feature = "server")]
+
+use std::{sync::Arc, thread, time::Duration};
+
+use badges::{DirectoryProvider, EndpointBadge, MemoryProvider, Server, ServerConfig};
+
+/// Run a server on an unused port for the duration of `f`.
+fn with_server(config: ServerConfig, f: impl FnOnce(&str)) {
+    let server = Arc::new(Server::bind("127.0.0.1:0", config).expect("failed to bind"));
+    let addr = server.local_addr().expect("server has no address");
+    let handle = thread::spawn({
+        let server = Arc::clone(&server);
+        move || server.run()
+    });
+    f(&format!("http://{addr}"));
+    server.shutdown();
+    handle.join().expect("server panicked");
+}
+
+fn agent() -> ureq::Agent {
+    ureq::Agent::config_builder()
+        .http_status_as_error(false)
+        .build()
+        .into()
+}
+
+#[test]
+fn test_static_badge() {
+    with_server(ServerConfig::new().cache_seconds(60), |base| {
+        let agent = agent();
+        let mut response = agent
+            .get(format!("{base}/badge/build-passing-brightgreen.svg"))
+            .call()
+            .expect("request failed");
+        assert_eq!(response.status(), 200);
+        let headers = response.headers();
+        assert_eq!(headers["content-type"], "image/svg+xml");
+        assert_eq!(headers["cache-control"], "max-age=60");
+        let etag = headers["etag"].to_str().expect("invalid ETag").to_owned();
+        let svg = response.body_mut().read_to_string().expect("invalid body");
+        assert!(svg.contains("<title>build: passing</title>"));
+        assert!(svg.contains(r##"fill="#44cc11""##));
+
+        let response = agent
+            .get(format!("{base}/badge/build-passing-brightgreen.svg"))
+            .header("If-None-Match", &etag)
+            .call()
+            .expect("request failed");
+        assert_eq!(response.status(), 304);
+
+        let mut response = agent
+            .get(format!("{base}/badge/a%20b--c-red.json?label=custom"))
+            .call()
+            .expect("request failed");
+        let json = response.body_mut().read_to_string().expect("invalid body");
+        assert!(json.contains(r#""label":"custom""#), "{json}");
+        assert!(json.contains(r#""message":"a b-c""#), "{json}");
+
+        let mut response = agent
+            .get(format!("{base}/badge/build-passing-blue.png"))
+            .call()
+            .expect("request failed");
+        assert_eq!(response.headers()["content-type"], "image/png");
+        let png = response.body_mut().read_to_vec().expect("invalid body");
+        assert!(png.starts_with(b"\x89PNG"));
+
+        let response = agent
+            .get(format!("{base}/nothing/here"))
+            .call()
+            .expect("request failed");
+        assert_eq!(response.status(), 404);
+    });
+}
+
+#[test]
+fn test_endpoint_badge() {
+    // a stand-in for the service publishing badge JSON
+    let endpoint = tiny_http::Server::http("127.0.0.1:0").expect("failed to bind");
+    let endpoint_addr = endpoint
+        .server_addr()
+        .to_ip()
+        .expect("endpoint has no address");
+    let endpoint_thread = thread::spawn(move || {
+        let request = endpoint.recv().expect("endpoint failed");
+        let body = r#"{"schemaVersion": 1, "label": "coverage", "message": "91%", "color": "green", "cacheSeconds": 3600}"#;
+        request
+            .respond(tiny_http::Response::from_string(body))
+            .expect("failed to respond");
+    });
+    let config = ServerConfig::new().allow_host(endpoint_addr.to_string());
+    with_server(config, |base| {
+        let agent = agent();
+        let url = format!("http%3A%2F%2F{endpoint_addr}%2Fcoverage.json");
+        let mut response = agent
+            .get(format!("{base}/endpoint?url={url}"))
+            .call()
+            .expect("request failed");
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers()["cache-control"], "max-age=3600");
+        let svg = response.body_mut().read_to_string().expect("invalid body");
+        assert!(svg.contains("<title>coverage: 91%</title>"));
+        assert!(svg.contains(r##"fill="#97ca00""##));
+
+        let response = agent
+            .get(format!(
+                "{base}/endpoint?url=https%3A%2F%2Fexample.com%2Fbadge.json"
+            ))
+            .call()
+            .expect("request failed");
+        assert_eq!(response.status(), 403);
+    });
+    endpoint_thread.join().expect("endpoint panicked");
+}
+
+#[test]
+fn test_endpoint_redirect_not_followed() {
+    // an allowed host redirecting to badge JSON elsewhere, which must not be fetched
+    let endpoint = tiny_http::Server::http("127.0.0.1:0").expect("failed to bind");
+    let endpoint_addr = endpoint
+        .server_addr()
+        .to_ip()
+        .expect("endpoint has no address");
+    let endpoint_thread = thread::spawn(move || {
+        let request = endpoint.recv().expect("endpoint failed");
+        let location =
+            tiny_http::Header::from_bytes("Location", "/internal.json").expect("invalid header");
+        request
+            .respond(tiny_http::Response::empty(302).with_header(location))
+            .expect("failed to respond");
+        endpoint
+            .recv_timeout(Duration::from_millis(500))
+            .expect("endpoint failed")
+            .is_none()
+    });
+    let config = ServerConfig::new().allow_host(endpoint_addr.to_string());
+    with_server(config, |base| {
+        let url = format!("http%3A%2F%2F{endpoint_addr}%2Fcoverage.json");
+        let response = agent()
+            .get(format!("{base}/endpoint?url={url}"))
+            .call()
+            .expect("request failed");
+        assert_eq!(response.status(), 502);
+    });
+    assert!(
+        endpoint_thread.join().expect("endpoint panicked"),
+        "redirect was followed"
+    );
+}
+
+#[test]
+fn test_local_logo_rejected() {
+    with_server(ServerConfig::new(), |base| {
+        let response = agent()
+            .get(format!("{base}/badge/a-b-green.png?logo=%2Fdev%2Fzero"))
+            .call()
+            .expect("request failed");
+        assert_eq!(response.status(), 400);
+    });
+}
+
+#[test]
+fn test_oversized_logo_width() {
+    with_server(ServerConfig::new(), |base| {
+        let agent = agent();
+        let mut response = agent
+            .get(format!(
+                "{base}/badge/a-b-green.png?logo=data%3A%2C&logoWidth=2000000"
+            ))
+            .call()
+            .expect("request failed");
+        assert_eq!(response.status(), 200);
+        let png = response.body_mut().read_to_vec().expect("invalid body");
+        let width = u32::from_be_bytes([png[16], png[17], png[18], png[19]]);
+        assert!(width < 200, "logo width was not clamped: {width}");
+
+        let response = agent
+            .get(format!("{base}/badge/a-b-green.svg?logoWidth=-1"))
+            .call()
+            .expect("request failed");
+        assert_eq!(response.status(), 400);
+    });
+}
+
+#[test]
+fn test_memory_provider() {
+    let provider = MemoryProvider::new();
+    provider.insert("coverage", EndpointBadge::new("coverage", "91%"));
+    let config = ServerConfig::new()
+        .provider(provider)
+        .update_token("secret");
+    with_server(config, |base| {
+        let agent = agent();
+        let mut response = agent
+            .get(format!("{base}/b/coverage.svg"))
+            .call()
+            .expect("request failed");
+        assert_eq!(response.status(), 200);
+        let svg = response.body_mut().read_to_string().expect("invalid body");
+        assert!(svg.contains("<title>coverage: 91%</title>"));
+
+        let update = r#"{"label": "coverage", "message": "93%", "color": "brightgreen"}"#;
+        let response = agent
+            .post(format!("{base}/b/coverage"))
+            .send(update)
+            .expect("request failed");
+        assert_eq!(response.status(), 401);
+        let response = agent
+            .post(format!("{base}/b/coverage"))
+            .header("Authorization", "Bearer secret")
+            .send(update)
+            .expect("request failed");
+        assert_eq!(response.status(), 204);
+
+        let mut response = agent
+            .get(format!("{base}/b/coverage.svg"))
+            .call()
+            .expect("request failed");
+        let svg = response.body_mut().read_to_string().expect("invalid body");
+        assert!(svg.contains("<title>coverage: 93%</title>"));
+
+        for path in ["/b/missing.svg", "/b/..%2Fcoverage.svg"] {
+            let response = agent
+                .get(format!("{base}{path}"))
+                .call()
+                .expect("request failed");
+            assert_eq!(response.status(), 404, "{path}");
+        }
+    });
+}
+
+#[test]
+fn test_directory_provider() {
+    let dir = std::env::temp_dir().join(format!("badges-server-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("bench")).expect("failed to create dir");
+    let write = |message: &str| {
+        let badge = EndpointBadge::new("throughput", message);
+        std::fs::write(dir.join("bench/throughput.json"), badge.to_json())
+            .expect("failed to write badge");
+    };
+    write("120 MB/s");
+    let config = ServerConfig::new().provider(DirectoryProvider::new(&dir));
+    with_server(config, |base| {
+        let agent = agent();
+        let get = || {
+            agent
+                .get(format!("{base}/b/bench/throughput.json"))
+                .call()
+                .expect("request failed")
+                .body_mut()
+                .read_to_string()
+                .expect("invalid body")
+        };
+        assert!(get().contains(r#""message":"120 MB/s""#));
+        write("135 MB/s");
+        assert!(get().contains(r#""message":"135 MB/s""#));
+
+        let response = agent
+            .post(format!("{base}/b/bench/throughput"))
+            .send(EndpointBadge::new("throughput", "0 MB/s").to_json())
+            .expect("request failed");
+        assert_eq!(response.status(), 405);
+        assert!(get().contains(r#""message":"135 MB/s""#));
+    });
+    std::fs::remove_dir_all(&dir).expect("failed to remove dir");
+}